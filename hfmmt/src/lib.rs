@@ -1,131 +1,890 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
 
 declare_id!("GsvEYrds1qtwamYbHJpUTx3jeEV6XrCSdxDy8UCf6y9H");
 
+// PDA seeds. Vaults and pools are derived from the config's key; per-user accounts from the
+// owner's key.
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const POOL_SEED: &[u8] = b"pool";
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+pub const GAS_VAULT_SEED: &[u8] = b"gas_vault";
+pub const INSURANCE_VAULT_SEED: &[u8] = b"insurance_vault";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const REWARD_VAULT_SEED: &[u8] = b"reward_vault";
+pub const LP_REWARD_VAULT_SEED: &[u8] = b"lp_reward_vault";
+pub const LP_MINT_SEED: &[u8] = b"lp_mint";
+pub const LP_STAKE_VAULT_SEED: &[u8] = b"lp_stake_vault";
+pub const FEE_REBATE_POOL_SEED: &[u8] = b"fee_rebate_pool";
+pub const GAS_FEE_POOL_SEED: &[u8] = b"gas_fee_pool";
+pub const INSURANCE_POOL_SEED: &[u8] = b"insurance_pool";
+pub const REWARD_POOL_SEED: &[u8] = b"reward_pool";
+pub const LP_REWARD_POOL_SEED: &[u8] = b"lp_reward_pool";
+pub const EPOCH_SEED: &[u8] = b"epoch";
+pub const REPORTER_REGISTRY_SEED: &[u8] = b"reporter_registry";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_RECORD_SEED: &[u8] = b"vote_record";
+pub const ORACLE_FEED_SEED: &[u8] = b"oracle_feed";
+pub const VOLATILITY_STATE_SEED: &[u8] = b"volatility_state";
+pub const PRIORITY_AUCTION_SEED: &[u8] = b"priority_auction";
+pub const AUCTION_ESCROW_SEED: &[u8] = b"auction_escrow";
+pub const PRIORITY_BID_SEED: &[u8] = b"priority_bid";
+pub const MARKET_MAKER_SEED: &[u8] = b"market_maker";
+pub const LIQUIDITY_PROVIDER_SEED: &[u8] = b"liquidity_provider";
+
+/// Fixed-point scale of `RewardPool::acc_reward_per_share`.
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+/// Basis points in 1x.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Fixed-point scale of price returns in `VolatilityState` (parts per million).
+pub const RETURN_PRECISION: u128 = 1_000_000;
+/// Minimum lock lengths, in seconds, for the 1.5x and 2x liquidity lock bonuses.
+pub const THREE_MONTH_LOCK: i64 = 90 * 86_400;
+pub const SIX_MONTH_LOCK: i64 = 180 * 86_400;
+/// Virtual shares and assets added to both sides of the LP share price, so a donation to a
+/// near-empty pool cannot round later depositors down to zero shares.
+pub const LP_VIRTUAL_OFFSET: u64 = 1_000;
+
+/// Pyth oracle program on mainnet; its price accounts can back the volatility oracle.
+pub mod pyth_mainnet {
+    use super::*;
+    declare_id!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+}
+
+/// Pyth oracle program on devnet.
+pub mod pyth_devnet {
+    use super::*;
+    declare_id!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+}
+
 #[program]
 pub mod hfmm_token {
     use super::*;
     
     /// Initialize the protocol's configuration.
     pub fn initialize(ctx: Context<Initialize>, config_params: ConfigParams) -> Result<()> {
+        config_params.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.user.key();
+        config.mint = ctx.accounts.mint.key();
+        config.bump = ctx.bumps.config;
+        config.authority_bump = ctx.bumps.vault_authority;
+        config.apply(&config_params);
+        Ok(())
+    }
+
+    /// Create the program-owned token accounts. Each is a PDA whose token authority is the
+    /// program's vault authority, so only the program can move funds out of them.
+    pub fn initialize_vaults(_ctx: Context<InitializeVaults>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Create the bookkeeping accounts for the staking reward, fee rebate, gas fee and
+    /// insurance pools, and open the first performance epoch.
+    pub fn initialize_pools(ctx: Context<InitializePools>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        ctx.accounts.reward_pool.last_update_time = current_time;
+        let epoch = &mut ctx.accounts.epoch;
+        epoch.start_time = current_time;
+        epoch.end_time = current_time.checked_add(ctx.accounts.config.epoch_duration).unwrap();
+        Ok(())
+    }
+
+    /// Permissionless crank that finalizes the current epoch once it has ended and opens the
+    /// next one. Makers' live counters are rolled into their epoch snapshot lazily, the next
+    /// time their account is touched. The keeper is paid the config's flat bounty.
+    pub fn advance_epoch(ctx: Context<AdvanceEpoch>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let epoch = &mut ctx.accounts.epoch;
+        require!(current_time >= epoch.end_time, CustomError::EpochNotEnded);
+        emit!(EpochAdvanced {
+            index: epoch.index,
+            total_volume: epoch.total_volume,
+            reported_volume: ctx.accounts.fee_rebate_pool.total_execution_volume,
+        });
+        ctx.accounts
+            .fee_rebate_pool
+            .roll(epoch.index, epoch.total_volume, epoch.total_boosted_volume)?;
+        epoch.total_volume = 0;
+        epoch.total_boosted_volume = 0;
+        epoch.index = epoch.index.checked_add(1).unwrap();
+        epoch.start_time = current_time;
+        epoch.end_time = current_time.checked_add(ctx.accounts.crank.config.epoch_duration).unwrap();
+        let keeper_flat_fee = ctx.accounts.crank.config.keeper_flat_fee;
+        ctx.accounts.crank.pay_flat_bounty(keeper_flat_fee)
+    }
+
+    /// Update the protocol's configuration. Only the admin may call this.
+    pub fn update_config(ctx: Context<UpdateConfig>, config_params: ConfigParams) -> Result<()> {
+        config_params.validate()?;
+        // Accrue at the old emission rate up to now before it can change.
+        let emission_rate = ctx.accounts.config.emission_rate;
+        ctx.accounts.reward_pool.update(emission_rate, Clock::get()?.unix_timestamp)?;
         let config = &mut ctx.accounts.config;
-        config.reward_rate = config_params.reward_rate;
-        config.volatility_threshold = config_params.volatility_threshold;
+        let old_params = config.params();
+        config.apply(&config_params);
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            old_params,
+            new_params: config_params,
+        });
         Ok(())
     }
     
+    /// Replace the volatility reward curve. Only the admin may call this. Breakpoints must have
+    /// strictly increasing volatility and non-decreasing multipliers; an empty curve falls back
+    /// to a 2x multiplier above `volatility_threshold`.
+    pub fn set_reward_curve(
+        ctx: Context<SetRewardCurve>,
+        breakpoints: Vec<RewardCurvePoint>,
+    ) -> Result<()> {
+        validate_reward_curve(&breakpoints)?;
+        let config = &mut ctx.accounts.config;
+        config.reward_curve = breakpoints;
+        emit!(RewardCurveUpdated {
+            admin: config.admin,
+            breakpoints: config.reward_curve.clone(),
+        });
+        Ok(())
+    }
+
+    /// Replace the priority tier ladder (e.g. Bronze, Silver, Gold). Only the admin may call
+    /// this. Tiers are ordered by strictly increasing stake threshold.
+    pub fn set_priority_tiers(ctx: Context<SetPriorityTiers>, tiers: Vec<PriorityTier>) -> Result<()> {
+        require!(
+            tiers.len() <= Config::MAX_PRIORITY_TIERS
+                && tiers.windows(2).all(|pair| pair[0].stake_threshold < pair[1].stake_threshold)
+                && tiers.iter().all(|tier| tier.rebate_boost_bps <= BPS_DENOMINATOR),
+            CustomError::InvalidPriorityTiers
+        );
+        let config = &mut ctx.accounts.config;
+        config.priority_tiers = tiers;
+        emit!(PriorityTiersUpdated {
+            admin: config.admin,
+            tiers: config.priority_tiers.clone(),
+        });
+        Ok(())
+    }
+
+    /// Register a market maker account for the signing wallet.
+    pub fn register_market_maker(ctx: Context<RegisterMarketMaker>) -> Result<()> {
+        let staker = &mut ctx.accounts.staker;
+        staker.owner = ctx.accounts.owner.key();
+        staker.bump = ctx.bumps.staker;
+        staker.reward_multiplier = BPS_DENOMINATOR;
+        Ok(())
+    }
+
+    /// Register a liquidity provider account for the signing wallet.
+    pub fn register_liquidity_provider(ctx: Context<RegisterLiquidityProvider>) -> Result<()> {
+        let provider = &mut ctx.accounts.liquidity_provider;
+        provider.owner = ctx.accounts.owner.key();
+        provider.bump = ctx.bumps.liquidity_provider;
+        provider.reward_multiplier = BPS_DENOMINATOR;
+        provider.last_accrual_time = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
     /// Market makers stake HFMMT tokens.
     pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
         token::transfer(ctx.accounts.into_transfer_to_vault_context(), amount)?;
+        let current_time = Clock::get()?.unix_timestamp;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(ctx.accounts.config.emission_rate, current_time)?;
         let staker = &mut ctx.accounts.staker;
+        staker.settle_rewards(reward_pool)?;
         staker.staked_amount = staker.staked_amount.checked_add(amount).unwrap();
+        staker.checkpoint_rewards(reward_pool)?;
+        staker.record_stake_checkpoint(current_time);
         // Update both last trade and stake timestamps.
-        let current_time = Clock::get()?.unix_timestamp;
         staker.last_trade_time = current_time;
         staker.last_stake_time = current_time;
         Ok(())
     }
     
-    /// Update market maker performance metrics.
+    /// Move staked tokens into the unbonding queue. They stop counting as stake immediately
+    /// and can be withdrawn with `complete_unstake` once the unbonding period has passed.
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        let config = &ctx.accounts.config;
+        let current_time = Clock::get()?.unix_timestamp;
+        let staker = &mut ctx.accounts.staker;
+        require!(staker.staked_amount >= amount, CustomError::InsufficientFunds);
+        require!(
+            staker.unbonding.len() < MarketMaker::MAX_UNBONDING_ENTRIES,
+            CustomError::TooManyUnbondingEntries
+        );
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(config.emission_rate, current_time)?;
+        staker.settle_rewards(reward_pool)?;
+        staker.staked_amount = staker.staked_amount.checked_sub(amount).unwrap();
+        staker.checkpoint_rewards(reward_pool)?;
+        staker.record_stake_checkpoint(current_time);
+        staker.refresh_priority(config);
+        let release_time = current_time.checked_add(config.unbonding_period).unwrap();
+        staker.unbonding.push(UnbondingEntry { amount, release_time });
+        Ok(())
+    }
+
+    /// Release every matured unbonding entry back to the owner.
+    pub fn complete_unstake(ctx: Context<CompleteUnstake>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let staker = &mut ctx.accounts.staker;
+        let matured_amount = staker
+            .unbonding
+            .iter()
+            .filter(|entry| entry.release_time <= current_time)
+            .try_fold(0u64, |total, entry| total.checked_add(entry.amount))
+            .unwrap();
+        require!(matured_amount > 0, CustomError::NothingToUnstake);
+        staker.unbonding.retain(|entry| entry.release_time > current_time);
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_to_staker_context(&[&seeds]), matured_amount)?;
+        Ok(())
+    }
+
+    /// Create the admin-managed registry of authorized performance reporters.
+    pub fn initialize_reporter_registry(_ctx: Context<InitializeReporterRegistry>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Authorize a reporter to submit performance updates.
+    pub fn add_reporter(ctx: Context<ManageReporters>, reporter: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.reporter_registry;
+        require!(!registry.is_reporter(&reporter), CustomError::ReporterAlreadyRegistered);
+        require!(
+            registry.reporters.len() < ReporterRegistry::MAX_REPORTERS,
+            CustomError::ReporterRegistryFull
+        );
+        registry.reporters.push(reporter);
+        Ok(())
+    }
+
+    /// Revoke a reporter's authorization.
+    pub fn remove_reporter(ctx: Context<ManageReporters>, reporter: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.reporter_registry;
+        require!(registry.is_reporter(&reporter), CustomError::UnauthorizedReporter);
+        registry.reporters.retain(|key| key != &reporter);
+        Ok(())
+    }
+
+    /// Update market maker performance metrics. Must be signed by a registered reporter.
     pub fn update_performance(
         ctx: Context<UpdatePerformance>,
         execution_volume: u64,
         spread_efficiency: u64,
         order_flow: u64,
+        nonce: u64,
     ) -> Result<()> {
-        let staker = &mut ctx.accounts.staker;
-        staker.execution_volume = staker.execution_volume.checked_add(execution_volume).unwrap();
-        staker.spread_efficiency = staker.spread_efficiency.checked_add(spread_efficiency).unwrap();
-        staker.order_flow = staker.order_flow.checked_add(order_flow).unwrap();
-        staker.last_trade_time = Clock::get()?.unix_timestamp;
-        Ok(())
+        require!(
+            ctx.accounts.reporter_registry.is_reporter(ctx.accounts.reporter.key),
+            CustomError::UnauthorizedReporter
+        );
+        ctx.accounts.staker.record_performance(
+            &mut ctx.accounts.epoch,
+            &ctx.accounts.config,
+            execution_volume,
+            spread_efficiency,
+            order_flow,
+            nonce,
+        )
+    }
+
+    /// Update market maker performance metrics from a report signed off-chain by a registered
+    /// reporter. The transaction must carry an ed25519 signature-verification instruction over
+    /// `performance_report_message` immediately before this one.
+    pub fn update_performance_with_attestation(
+        ctx: Context<UpdatePerformanceWithAttestation>,
+        execution_volume: u64,
+        spread_efficiency: u64,
+        order_flow: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        let message = performance_report_message(
+            &ctx.accounts.staker.key(),
+            execution_volume,
+            spread_efficiency,
+            order_flow,
+            nonce,
+        );
+        let reporter = verify_ed25519_attestation(&ctx.accounts.instructions, &message)?;
+        require!(
+            ctx.accounts.reporter_registry.is_reporter(&reporter),
+            CustomError::UnauthorizedReporter
+        );
+        ctx.accounts.staker.record_performance(
+            &mut ctx.accounts.epoch,
+            &ctx.accounts.config,
+            execution_volume,
+            spread_efficiency,
+            order_flow,
+            nonce,
+        )
     }
     
-    /// Calculate and distribute rewards to market makers.
-    /// If auto-compound is enabled, rewards are added to the staked amount.
+    /// Calculate and distribute rewards to market makers: the staking emission accrued since
+    /// the last checkpoint plus a bonus for the maker's last finalized epoch, paid once.
+    /// Both are paid from the funded reward vault; the bonus is capped at its unemitted funds.
+    /// If auto-compound is enabled, rewards are moved into the staking vault and staked.
     pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
-        let staker = &mut ctx.accounts.staker;
         let config = &ctx.accounts.config;
-        // Example weighted score: 2×execution + 3×spread efficiency + 1×order flow.
-        let score = staker.execution_volume.checked_mul(2).unwrap()
-            .checked_add(staker.spread_efficiency.checked_mul(3).unwrap()).unwrap()
-            .checked_add(staker.order_flow).unwrap();
-        let reward_amount = score.checked_div(config.reward_rate).unwrap();
-        if staker.auto_compound {
+        let current_time = Clock::get()?.unix_timestamp;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(config.emission_rate, current_time)?;
+        let staker = &mut ctx.accounts.staker;
+        staker.roll_epoch(&ctx.accounts.epoch, &config.scoring_weights)?;
+        staker.settle_rewards(reward_pool)?;
+        let mut performance_reward = 0;
+        if !staker.last_epoch.rewarded {
+            performance_reward = apply_multiplier(
+                staker.last_epoch.score.checked_div(config.reward_rate).unwrap(),
+                staker.reward_multiplier,
+            )?
+            .min(reward_pool.unemitted);
+            reward_pool.unemitted = reward_pool.unemitted.checked_sub(performance_reward).unwrap();
+            staker.last_epoch.rewarded = true;
+        }
+        let reward_amount = staker.pending_rewards.checked_add(performance_reward).unwrap();
+        staker.pending_rewards = 0;
+        let auto_compound = staker.auto_compound;
+        if auto_compound {
             staker.staked_amount = staker.staked_amount.checked_add(reward_amount).unwrap();
+            staker.checkpoint_rewards(reward_pool)?;
+            staker.record_stake_checkpoint(current_time);
+        }
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        if auto_compound {
+            token::transfer(ctx.accounts.into_compound_context(&[&seeds]), reward_amount)?;
         } else {
-            token::transfer(ctx.accounts.into_transfer_to_staker_context(), reward_amount)?;
+            token::transfer(ctx.accounts.into_transfer_to_staker_context(&[&seeds]), reward_amount)?;
         }
         Ok(())
     }
+
+    /// Fund staking emissions. Tokens move into the reward vault and extend the budget that
+    /// `emission_rate` draws down; emissions stop once it is spent.
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        // Accrue up to now first, so the new funds are not emitted retroactively.
+        let emission_rate = ctx.accounts.config.emission_rate;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(emission_rate, Clock::get()?.unix_timestamp)?;
+        reward_pool.unemitted = reward_pool.unemitted.checked_add(amount).unwrap();
+        token::transfer(ctx.accounts.into_transfer_to_reward_vault_context(), amount)
+    }
+
+    /// Fund liquidity rewards. Tokens move into the LP reward vault and extend the budget that
+    /// providers' rewards accrue against; accrual stops once it is spent.
+    pub fn fund_lp_rewards(ctx: Context<FundLpRewards>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        let lp_reward_pool = &mut ctx.accounts.lp_reward_pool;
+        lp_reward_pool.unemitted = lp_reward_pool.unemitted.checked_add(amount).unwrap();
+        token::transfer(ctx.accounts.into_transfer_to_lp_reward_vault_context(), amount)
+    }
     
-    /// Liquidity providers add tokens to the pool.
+    /// Liquidity providers add tokens to the pool and are minted LP shares in proportion to
+    /// their contribution to pool assets. Shares are freely transferable; stake them with
+    /// `stake_lp_shares` to earn liquidity rewards.
     pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<()> {
-        let liquidity_provider = &mut ctx.accounts.liquidity_provider;
-        liquidity_provider.liquidity = liquidity_provider.liquidity.checked_add(amount).unwrap();
-        liquidity_provider.last_deposit_time = Clock::get()?.unix_timestamp;
+        let shares = shares_for_deposit(amount, ctx.accounts.pool.amount, ctx.accounts.lp_mint.supply)?;
+        require!(shares > 0, CustomError::InvalidAmount);
         token::transfer(ctx.accounts.into_transfer_to_pool_context(), amount)?;
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::mint_to(ctx.accounts.into_mint_shares_context(&[&seeds]), shares)?;
         Ok(())
     }
     
-    /// Liquidity providers withdraw tokens from the pool.
-    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, amount: u64) -> Result<()> {
+    /// Any holder of LP shares burns them from their own token account and withdraws their
+    /// value at the current share price.
+    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>, shares: u64) -> Result<()> {
+        require!(shares > 0, CustomError::InvalidAmount);
+        let amount = assets_for_shares(shares, ctx.accounts.pool.amount, ctx.accounts.lp_mint.supply)?;
+        token::burn(ctx.accounts.into_burn_shares_context(), shares)?;
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_to_user_context(&[&seeds]), amount)?;
+        Ok(())
+    }
+
+    /// Stake LP shares into the program's custody. Liquidity rewards, locks and insurance are
+    /// all based on staked shares, so rewards only accrue on shares the provider actually holds.
+    pub fn stake_lp_shares(ctx: Context<StakeLpShares>, shares: u64) -> Result<()> {
+        require!(shares > 0, CustomError::InvalidAmount);
+        let current_time = Clock::get()?.unix_timestamp;
+        let lp_reward_rate = ctx.accounts.config.lp_reward_rate;
+        let liquidity_provider = &mut ctx.accounts.liquidity_provider;
+        liquidity_provider.accrue_rewards(&mut ctx.accounts.lp_reward_pool, lp_reward_rate, current_time)?;
+        liquidity_provider.liquidity = liquidity_provider.liquidity.checked_add(shares).unwrap();
+        liquidity_provider.last_deposit_time = current_time;
+        token::transfer(ctx.accounts.into_transfer_to_stake_vault_context(), shares)
+    }
+
+    /// Return staked LP shares to the provider's wallet. Locked shares can only leave early if
+    /// the config sets an early-exit penalty: that share of them is burned and its value is
+    /// sent to the treasury.
+    pub fn unstake_lp_shares(ctx: Context<UnstakeLpShares>, shares: u64) -> Result<()> {
+        require!(shares > 0, CustomError::InvalidAmount);
+        let current_time = Clock::get()?.unix_timestamp;
+        let penalty_bps = ctx.accounts.config.early_exit_penalty_bps;
         let liquidity_provider = &mut ctx.accounts.liquidity_provider;
-        require!(liquidity_provider.liquidity >= amount, CustomError::InsufficientFunds);
-        liquidity_provider.liquidity = liquidity_provider.liquidity.checked_sub(amount).unwrap();
-        token::transfer(ctx.accounts.into_transfer_to_user_context(), amount)?;
+        require!(liquidity_provider.liquidity >= shares, CustomError::InsufficientFunds);
+        let lp_reward_rate = ctx.accounts.config.lp_reward_rate;
+        liquidity_provider.accrue_rewards(&mut ctx.accounts.lp_reward_pool, lp_reward_rate, current_time)?;
+        let mut penalty_shares = 0;
+        if current_time < liquidity_provider.unlock_at {
+            require!(penalty_bps > 0, CustomError::LiquidityLocked);
+            // Rounded up, so splitting an exit into small unstakes cannot dodge the penalty.
+            penalty_shares = apply_multiplier_ceil(shares, penalty_bps)?;
+        }
+        liquidity_provider.liquidity = liquidity_provider.liquidity.checked_sub(shares).unwrap();
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        if penalty_shares > 0 {
+            let penalty = assets_for_shares(
+                penalty_shares,
+                ctx.accounts.pool.amount,
+                ctx.accounts.lp_mint.supply,
+            )?;
+            token::burn(ctx.accounts.into_burn_penalty_context(&[&seeds]), penalty_shares)?;
+            token::transfer(ctx.accounts.into_transfer_penalty_context(&[&seeds]), penalty)?;
+        }
+        token::transfer(
+            ctx.accounts.into_transfer_to_owner_context(&[&seeds]),
+            shares.checked_sub(penalty_shares).unwrap(),
+        )?;
         Ok(())
     }
     
-    /// Request priority execution for market makers.
+    /// Request priority execution for market makers. Issues, or renews, a priority ticket
+    /// valid for the config's `priority_ticket_slots` while stake is at or above
+    /// `priority_stake_threshold`, at the highest tier the maker's stake qualifies for. Refused
+    /// while `auction_slots` is set, since tickets are then only issued to auction winners.
     pub fn request_priority_execution(ctx: Context<RequestPriority>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(config.auction_slots == 0, CustomError::PriorityAuctioned);
         let staker = &mut ctx.accounts.staker;
-        require!(staker.staked_amount >= 1000, CustomError::NotEnoughStake);
-        staker.has_priority_access = true;
+        require!(
+            staker.staked_amount >= config.priority_stake_threshold,
+            CustomError::NotEnoughStake
+        );
+        let expiry_slot = Clock::get()?.slot.checked_add(config.priority_ticket_slots).unwrap();
+        staker.issue_priority_ticket(config, expiry_slot);
+        Ok(())
+    }
+
+    /// Read-only view of a maker's current priority tier: `None` without a valid ticket or
+    /// below every configured tier.
+    pub fn get_priority_tier(ctx: Context<GetPriorityTier>) -> Result<Option<u8>> {
+        let staker = &ctx.accounts.staker;
+        if !staker.has_priority_access(Clock::get()?.slot) {
+            return Ok(None);
+        }
+        Ok(staker.priority_tier)
+    }
+
+    /// Open the current epoch's priority slot auction. Anyone may open it; bidding runs for the
+    /// config's `auction_duration` seconds and the `auction_slots` highest bids win.
+    pub fn open_priority_auction(ctx: Context<OpenPriorityAuction>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(config.auction_slots > 0, CustomError::PriorityNotAuctioned);
+        let auction = &mut ctx.accounts.auction;
+        auction.epoch_index = ctx.accounts.epoch.index;
+        auction.bump = ctx.bumps.auction;
+        auction.end_time = Clock::get()?
+            .unix_timestamp
+            .checked_add(config.auction_duration)
+            .unwrap();
+        auction.slots = config.auction_slots;
+        emit!(PriorityAuctionOpened {
+            epoch_index: auction.epoch_index,
+            end_time: auction.end_time,
+            slots: auction.slots,
+        });
+        Ok(())
+    }
+
+    /// Bid `amount` HFMMT for a priority slot, escrowed until the auction settles. Makers need
+    /// the config's `priority_stake_threshold` to bid, and a bid must enter the current top
+    /// `slots` to be accepted.
+    pub fn place_priority_bid(ctx: Context<PlacePriorityBid>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        require!(
+            ctx.accounts.staker.staked_amount >= ctx.accounts.config.priority_stake_threshold,
+            CustomError::NotEnoughStake
+        );
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.auction.end_time,
+            CustomError::AuctionClosed
+        );
+        let staker_key = ctx.accounts.staker.key();
+        ctx.accounts.auction.record_bid(staker_key, amount)?;
+        token::transfer(ctx.accounts.into_transfer_to_escrow_context(), amount)?;
+        let bid = &mut ctx.accounts.bid;
+        bid.auction = ctx.accounts.auction.key();
+        bid.staker = staker_key;
+        bid.owner = ctx.accounts.owner.key();
+        bid.bump = ctx.bumps.bid;
+        bid.amount = amount;
+        emit!(PriorityBidPlaced {
+            epoch_index: ctx.accounts.auction.epoch_index,
+            owner: bid.owner,
+            amount: bid.amount,
+        });
+        Ok(())
+    }
+
+    /// Add `amount` HFMMT to an existing bid, e.g. after being outbid. The maker must still hold
+    /// the config's `priority_stake_threshold`.
+    pub fn raise_priority_bid(ctx: Context<RaisePriorityBid>, amount: u64) -> Result<()> {
+        require!(amount > 0, CustomError::InvalidAmount);
+        require!(
+            ctx.accounts.staker.staked_amount >= ctx.accounts.config.priority_stake_threshold,
+            CustomError::NotEnoughStake
+        );
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.auction.end_time,
+            CustomError::AuctionClosed
+        );
+        let bid = &mut ctx.accounts.bid;
+        bid.amount = bid.amount.checked_add(amount).unwrap();
+        let (staker_key, total) = (bid.staker, bid.amount);
+        ctx.accounts.auction.record_bid(staker_key, total)?;
+        token::transfer(ctx.accounts.into_transfer_to_escrow_context(), amount)?;
+        emit!(PriorityBidPlaced {
+            epoch_index: ctx.accounts.auction.epoch_index,
+            owner: ctx.accounts.bid.owner,
+            amount: total,
+        });
+        Ok(())
+    }
+
+    /// Permissionless crank that closes bidding once the auction has ended and moves the
+    /// winning bids from escrow to the treasury. The leaders' `MarketMaker` accounts are passed
+    /// as remaining accounts, in order, and a leader below the config's
+    /// `priority_stake_threshold` forfeits its bid without a ticket. Winners' tickets run for
+    /// the config's `priority_ticket_slots` from settlement. The keeper is paid the config's
+    /// flat bounty.
+    pub fn settle_priority_auction(ctx: Context<SettlePriorityAuction>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(!auction.settled, CustomError::AuctionSettled);
+        require!(
+            Clock::get()?.unix_timestamp >= auction.end_time,
+            CustomError::AuctionNotEnded
+        );
+        require!(
+            ctx.remaining_accounts.len() == auction.leaders.len(),
+            CustomError::LeaderAccountMismatch
+        );
+        let proceeds = auction
+            .leaders
+            .iter()
+            .try_fold(0u64, |total, leader| total.checked_add(leader.amount))
+            .unwrap();
+        if proceeds > 0 {
+            let config_key = ctx.accounts.crank.config.key();
+            let seeds = ctx.accounts.crank.config.authority_seeds(&config_key);
+            token::transfer(ctx.accounts.into_transfer_proceeds_context(&[&seeds]), proceeds)?;
+        }
+        let threshold = ctx.accounts.crank.config.priority_stake_threshold;
+        let auction = &mut ctx.accounts.auction;
+        for (leader, info) in auction.leaders.iter_mut().zip(ctx.remaining_accounts) {
+            require!(
+                info.key == &leader.bidder && info.owner == &crate::ID,
+                CustomError::LeaderAccountMismatch
+            );
+            let staker = MarketMaker::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            leader.forfeited = staker.staked_amount < threshold;
+        }
+        auction.settled = true;
+        auction.ticket_expiry_slot = Clock::get()?
+            .slot
+            .checked_add(ctx.accounts.crank.config.priority_ticket_slots)
+            .unwrap();
+        emit!(PriorityAuctionSettled {
+            epoch_index: auction.epoch_index,
+            winners: auction
+                .leaders
+                .iter()
+                .filter(|leader| !leader.forfeited)
+                .map(|leader| leader.bidder)
+                .collect(),
+            proceeds,
+        });
+        let keeper_flat_fee = ctx.accounts.crank.config.keeper_flat_fee;
+        ctx.accounts.crank.pay_flat_bounty(keeper_flat_fee)
+    }
+
+    /// Close a bid on a settled auction. Winners still holding the config's
+    /// `priority_stake_threshold` receive a priority ticket that expires with the auction's
+    /// term. A winning bid is forfeited, with no ticket or refund, if the winner fell below the
+    /// threshold at settlement or since, or claims after the term has ended. Losing bids are
+    /// refunded from escrow.
+    pub fn claim_priority_bid(ctx: Context<ClaimPriorityBid>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(auction.settled, CustomError::AuctionNotSettled);
+        let staker_key = ctx.accounts.staker.key();
+        if let Some(leader) = auction.leaders.iter().find(|leader| leader.bidder == staker_key) {
+            let config = &ctx.accounts.config;
+            if !leader.forfeited
+                && ctx.accounts.staker.staked_amount >= config.priority_stake_threshold
+                && Clock::get()?.slot < auction.ticket_expiry_slot
+            {
+                let expiry_slot = auction.ticket_expiry_slot;
+                ctx.accounts.staker.issue_priority_ticket(config, expiry_slot);
+            }
+            return Ok(());
+        }
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_refund_context(&[&seeds]), ctx.accounts.bid.amount)?;
         Ok(())
     }
+
+    /// Stable CPI entry point for matching engines and DEX programs: fails with
+    /// `NoPriorityTicket` unless `maker` holds a valid priority ticket at the current slot, and
+    /// returns the ticket's tier as return data. Callers link this crate with the `cpi` feature
+    /// and invoke `hfmmt::cpi::verify_priority`.
+    pub fn verify_priority(ctx: Context<VerifyPriority>) -> Result<Option<u8>> {
+        let staker = &ctx.accounts.staker;
+        require!(
+            staker.has_priority_access(Clock::get()?.slot),
+            CustomError::NoPriorityTicket
+        );
+        Ok(staker.priority_tier)
+    }
     
-    /// Governance voting for protocol optimizations.
-    pub fn vote(ctx: Context<Vote>, proposal_id: u64, vote_weight: u64) -> Result<()> {
-        let voter = &mut ctx.accounts.voter;
+    /// Open a governance proposal. Voting runs for `voting_period` seconds from now, which must
+    /// be at least the config's `min_voting_period`; if it passes, `action` can be executed once
+    /// the config's execution delay has elapsed.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        description_hash: [u8; 32],
+        voting_period: i64,
+        action: ProposalAction,
+    ) -> Result<()> {
+        action.validate()?;
+        let current_time = Clock::get()?.unix_timestamp;
+        let config = &mut ctx.accounts.config;
+        require!(voting_period >= config.min_voting_period, CustomError::InvalidVotingPeriod);
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.proposal_id = config.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.bump = ctx.bumps.proposal;
+        proposal.description_hash = description_hash;
+        proposal.action = action;
+        proposal.config_version = config.config_version;
+        proposal.voting_start = current_time;
+        proposal.voting_end = current_time.checked_add(voting_period).unwrap();
+        proposal.status = ProposalStatus::Active;
+        config.proposal_count = config.proposal_count.checked_add(1).unwrap();
+        emit!(ProposalCreated {
+            proposal_id: proposal.proposal_id,
+            proposer: proposal.proposer,
+            voting_end: proposal.voting_end,
+        });
+        Ok(())
+    }
+
+    /// Let `delegate` vote with this market maker's stake. While delegated, the owner cannot
+    /// vote with it.
+    pub fn delegate_votes(ctx: Context<ManageDelegation>, delegate: Pubkey) -> Result<()> {
+        let staker = &mut ctx.accounts.staker;
+        require!(delegate != staker.owner, CustomError::InvalidDelegate);
+        staker.delegate = Some(delegate);
+        Ok(())
+    }
+
+    /// Return voting power to the owner.
+    pub fn undelegate_votes(ctx: Context<ManageDelegation>) -> Result<()> {
+        ctx.accounts.staker.delegate = None;
+        Ok(())
+    }
+
+    /// Governance voting for protocol optimizations. Voting power is the market maker's
+    /// stake as of the proposal's creation, and each maker gets one `VoteRecord` per proposal
+    /// whether the vote is cast by its owner or its delegate.
+    pub fn cast_vote(ctx: Context<CastVote>, choice: VoteChoice) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.require_voting_open(Clock::get()?.unix_timestamp)?;
+        let weight = ctx.accounts.staker.stake_at(proposal.voting_start);
+        require!(weight > 0, CustomError::NotEnoughTokens);
+        proposal.add_votes(choice, weight);
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.staker = ctx.accounts.staker.key();
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.bump = ctx.bumps.vote_record;
+        vote_record.choice = choice;
+        vote_record.weight = weight;
+        Ok(())
+    }
+
+    /// Move an existing vote to a different choice while voting is still open.
+    pub fn change_vote(ctx: Context<ChangeVote>, choice: VoteChoice) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.require_voting_open(Clock::get()?.unix_timestamp)?;
+        let vote_record = &mut ctx.accounts.vote_record;
+        proposal.remove_votes(vote_record.choice, vote_record.weight);
+        proposal.add_votes(choice, vote_record.weight);
+        vote_record.choice = choice;
+        vote_record.voter = ctx.accounts.voter.key();
+        Ok(())
+    }
+
+    /// Close voting once the window has ended. A proposal passes if turnout meets the quorum
+    /// and the `for` share of decisive (non-abstain) votes meets the approval threshold.
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let config = &ctx.accounts.config;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(proposal.status == ProposalStatus::Active, CustomError::ProposalNotActive);
+        require!(current_time >= proposal.voting_end, CustomError::VotingNotEnded);
+        if proposal.passes(config.quorum_votes, config.approval_threshold_bps) {
+            proposal.status = ProposalStatus::Succeeded;
+            proposal.executable_at = current_time.checked_add(config.execution_delay).unwrap();
+        } else {
+            proposal.status = ProposalStatus::Defeated;
+        }
+        emit!(ProposalFinalized {
+            proposal_id: proposal.proposal_id,
+            status: proposal.status,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            votes_abstain: proposal.votes_abstain,
+        });
+        Ok(())
+    }
+
+    /// Apply a passed proposal's action once its timelock has elapsed.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            ctx.accounts.proposal.status == ProposalStatus::Succeeded,
+            CustomError::ProposalNotSucceeded
+        );
+        require!(
+            current_time >= ctx.accounts.proposal.executable_at,
+            CustomError::TimelockNotElapsed
+        );
+        match ctx.accounts.proposal.action.clone() {
+            ProposalAction::UpdateConfig { params } => {
+                // `params` is a full snapshot, so applying it after another update would
+                // silently revert that update.
+                require!(
+                    ctx.accounts.proposal.config_version == ctx.accounts.config.config_version,
+                    CustomError::StaleProposal
+                );
+                params.validate()?;
+                let emission_rate = ctx.accounts.config.emission_rate;
+                ctx.accounts.reward_pool.update(emission_rate, current_time)?;
+                let config = &mut ctx.accounts.config;
+                let old_params = config.params();
+                config.apply(&params);
+                emit!(ConfigUpdated {
+                    admin: config.admin,
+                    old_params,
+                    new_params: params,
+                });
+            }
+        }
         let proposal = &mut ctx.accounts.proposal;
-        require!(voter.token_balance >= vote_weight, CustomError::NotEnoughTokens);
-        proposal.votes = proposal.votes.checked_add(vote_weight).unwrap();
+        proposal.status = ProposalStatus::Executed;
+        emit!(ProposalExecuted { proposal_id: proposal.proposal_id });
         Ok(())
     }
     
     ///  Dynamic Fee Rebates for Market Makers.
+    /// Each maker's rebate is its share of the last finalized epoch's boosted volume, applied to
+    /// the fees owed to makers that epoch, and can be claimed once per epoch. Volume reported
+    /// while the maker held a priority ticket counts with its tier's `rebate_boost_bps`.
     pub fn claim_fee_rebate(ctx: Context<ClaimFeeRebate>) -> Result<()> {
-        let staker_execution_volume = ctx.accounts.staker.execution_volume;
-        let fee_total_fees = ctx.accounts.fee_rebate_pool.total_fees;
-        let fee_total_exec = ctx.accounts.fee_rebate_pool.total_execution_volume;
-        let rebate_amount = fee_total_fees
-            .checked_mul(staker_execution_volume)
-            .unwrap()
-            .checked_div(fee_total_exec)
-            .unwrap();
+        let staker = &mut ctx.accounts.staker;
+        staker.roll_epoch(&ctx.accounts.epoch, &ctx.accounts.config.scoring_weights)?;
+        let fee_pool = &mut ctx.accounts.fee_rebate_pool;
+        require!(
+            staker.last_epoch.epoch_index == fee_pool.last_epoch_index
+                && !staker.last_epoch.rebate_claimed,
+            CustomError::NoRebateAvailable
+        );
+        let rebate_amount = fee_pool.rebate_for(staker.last_epoch.boosted_volume)?;
         require!(rebate_amount > 0, CustomError::NoRebateAvailable);
-        token::transfer(ctx.accounts.into_transfer_rebate_context(), rebate_amount)?;
+        staker.last_epoch.rebate_claimed = true;
+        fee_pool.last_epoch_unclaimed = fee_pool.last_epoch_unclaimed.checked_sub(rebate_amount).unwrap();
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_rebate_context(&[&seeds]), rebate_amount)?;
+        Ok(())
+    }
+
+    /// Deposit trading fees collected for the current epoch into the fee vault, together with
+    /// the execution volume they were earned on. Only registered reporters may deposit. The
+    /// reported volume cross-checks the makers' summed volume when the epoch is finalized.
+    pub fn deposit_fees(ctx: Context<DepositFees>, fees: u64, execution_volume: u64) -> Result<()> {
+        require!(
+            ctx.accounts.reporter_registry.is_reporter(ctx.accounts.depositor.key),
+            CustomError::UnauthorizedReporter
+        );
+        require!(fees > 0, CustomError::InvalidAmount);
+        token::transfer(ctx.accounts.into_transfer_fees_context(), fees)?;
         let fee_pool = &mut ctx.accounts.fee_rebate_pool;
-        fee_pool.total_fees = fee_pool.total_fees.checked_sub(rebate_amount).unwrap();
+        fee_pool.total_fees = fee_pool.total_fees.checked_add(fees).unwrap();
+        fee_pool.total_execution_volume = fee_pool
+            .total_execution_volume
+            .checked_add(execution_volume)
+            .unwrap();
         Ok(())
     }
     
-    /// Time-Based Liquidity Rewards.
-    pub fn lock_liquidity(ctx: Context<LockLiquidity>, lock_duration: u64) -> Result<()> {
+    /// Time-Based Liquidity Rewards. `lock_duration` is in seconds; a lock can be extended but
+    /// never shortened.
+    pub fn lock_liquidity(ctx: Context<LockLiquidity>, lock_duration: i64) -> Result<()> {
+        require!(lock_duration > 0, CustomError::InvalidAmount);
+        let current_time = Clock::get()?.unix_timestamp;
+        let unlock_at = current_time.checked_add(lock_duration).unwrap();
         let provider = &mut ctx.accounts.liquidity_provider;
-        provider.lock_duration = lock_duration;
+        require!(unlock_at >= provider.unlock_at, CustomError::LockCannotBeShortened);
+        // Settle at the old multiplier before the lock bonus changes.
+        let lp_reward_rate = ctx.accounts.config.lp_reward_rate;
+        provider.accrue_rewards(&mut ctx.accounts.lp_reward_pool, lp_reward_rate, current_time)?;
+        provider.lock_start = current_time;
+        provider.unlock_at = unlock_at;
         // Set reward multiplier: 1-month = 1x, 3-month = 1.5x, 6-month = 2x.
-        match lock_duration {
-            3 => provider.reward_multiplier = 150,
-            6 => provider.reward_multiplier = 200,
-            _ => provider.reward_multiplier = 100,
-        }
+        provider.reward_multiplier = if lock_duration >= SIX_MONTH_LOCK {
+            20_000
+        } else if lock_duration >= THREE_MONTH_LOCK {
+            15_000
+        } else {
+            BPS_DENOMINATOR
+        };
         Ok(())
     }
     
+    /// Permissionless cleanup that clears an expired liquidity lock, settling the boosted
+    /// rewards earned up to `unlock_at` and resetting the multiplier to 1x. Accrual already
+    /// stops the boost at `unlock_at`, so this is bookkeeping only and pays no bounty.
+    pub fn expire_lock(ctx: Context<ExpireLock>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let lp_reward_rate = ctx.accounts.config.lp_reward_rate;
+        let provider = &mut ctx.accounts.liquidity_provider;
+        require!(
+            provider.lock_start != 0 && current_time >= provider.unlock_at,
+            CustomError::LockNotExpired
+        );
+        provider.accrue_rewards(&mut ctx.accounts.lp_reward_pool, lp_reward_rate, current_time)?;
+        provider.lock_start = 0;
+        provider.reward_multiplier = BPS_DENOMINATOR;
+        Ok(())
+    }
+
     ///  Auto-Compounding Staking.
     pub fn enable_auto_compound(ctx: Context<EnableAutoCompound>) -> Result<()> {
         let staker = &mut ctx.accounts.staker;
@@ -134,31 +893,70 @@ pub mod hfmm_token {
     }
     
     ///  Flash Loan Resistance for Liquidity Providers.
+    /// Rewards are paid from the funded LP reward vault, never from the pool's principal or
+    /// the treasury. Accrued rewards are already reserved from its budget.
     pub fn claim_liquidity_rewards(ctx: Context<ClaimLiquidityRewards>) -> Result<()> {
         let current_time = Clock::get()?.unix_timestamp;
         let last_deposit_time = ctx.accounts.liquidity_provider.last_deposit_time;
         require!(current_time > last_deposit_time + 604800, CustomError::FlashLoanDetected);
-        let reward = ctx.accounts.liquidity_provider.reward_balance;
-        require!(reward > 0, CustomError::NoRewardAvailable);
-        token::transfer(ctx.accounts.into_transfer_rewards_context(), reward)?;
+        let lp_reward_rate = ctx.accounts.config.lp_reward_rate;
         let liquidity_provider = &mut ctx.accounts.liquidity_provider;
+        liquidity_provider.accrue_rewards(&mut ctx.accounts.lp_reward_pool, lp_reward_rate, current_time)?;
+        let reward = liquidity_provider.reward_balance;
+        require!(reward > 0, CustomError::NoRewardAvailable);
         liquidity_provider.reward_balance = 0;
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_rewards_context(&[&seeds]), reward)?;
         Ok(())
     }
     
-    ///  Staking Slashing for Inactivity.
+    ///  Staking Slashing for Inactivity. A maker that has not traded for the config's
+    /// `inactivity_period` loses `slash_bps` of its stake, at most once per inactivity period.
+    /// Unbonding entries are still in the vault and are slashed too, so requesting an unstake
+    /// does not escape a slash. The keeper receives `keeper_bounty_bps` of the slash and the
+    /// rest goes to the treasury.
     pub fn enforce_activity_slashing(ctx: Context<EnforceSlashing>) -> Result<()> {
+        let config = &ctx.accounts.crank.config;
         let staker = &mut ctx.accounts.staker;
         let current_time = Clock::get()?.unix_timestamp;
-        if current_time > staker.last_trade_time + 2592000 {
-            let slash_amount = staker.staked_amount / 10;
-            staker.staked_amount = staker.staked_amount.checked_sub(slash_amount).unwrap();
-            token::transfer(ctx.accounts.into_transfer_slash_context(), slash_amount)?;
+        require!(
+            current_time > staker.last_trade_time.checked_add(config.inactivity_period).unwrap()
+                && current_time >= staker.last_slashed_at.checked_add(config.inactivity_period).unwrap(),
+            CustomError::SlashingNotDue
+        );
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(config.emission_rate, current_time)?;
+        staker.settle_rewards(reward_pool)?;
+        let mut slash_amount = apply_multiplier(staker.staked_amount, config.slash_bps)?;
+        staker.staked_amount = staker.staked_amount.checked_sub(slash_amount).unwrap();
+        for entry in staker.unbonding.iter_mut() {
+            let entry_slash = apply_multiplier(entry.amount, config.slash_bps)?;
+            entry.amount = entry.amount.checked_sub(entry_slash).unwrap();
+            slash_amount = slash_amount.checked_add(entry_slash).unwrap();
         }
+        staker.checkpoint_rewards(reward_pool)?;
+        staker.record_stake_checkpoint(current_time);
+        staker.refresh_priority(config);
+        staker.last_slashed_at = current_time;
+        let bounty = apply_multiplier(slash_amount, config.keeper_bounty_bps)?;
+        let config_key = config.key();
+        let seeds = config.authority_seeds(&config_key);
+        if bounty > 0 {
+            token::transfer(ctx.accounts.into_transfer_bounty_context(&[&seeds]), bounty)?;
+            emit!(KeeperBountyPaid {
+                keeper: ctx.accounts.crank.keeper.key(),
+                amount: bounty,
+            });
+        }
+        token::transfer(
+            ctx.accounts.into_transfer_slash_context(&[&seeds]),
+            slash_amount.checked_sub(bounty).unwrap(),
+        )?;
         Ok(())
     }
     
-    /// --- Additional Enhancements ---
+    // --- Additional Enhancements ---
     
     /// Enforce a minimum trade volume to help prevent Sybil attacks.
     pub fn enforce_min_trade_volume(ctx: Context<EnforceTradeVolume>) -> Result<()> {
@@ -175,23 +973,86 @@ pub mod hfmm_token {
         Ok(())
     }
     
-    /// Adjust rewards dynamically based on market volatility.
-    pub fn adjust_rewards_by_volatility(ctx: Context<AdjustRewards>, volatility_index: u64) -> Result<()> {
+    /// Create an oracle feed published by the signing authority. The feed the program reads is
+    /// selected by `Config.volatility_oracle`; in tests this local feed stands in for a Pyth
+    /// price account. Refused unless the program is built with the `mock-oracle` feature.
+    pub fn initialize_oracle_feed(ctx: Context<InitializeOracleFeed>) -> Result<()> {
+        require!(cfg!(feature = "mock-oracle"), CustomError::MockOracleDisabled);
+        let feed = &mut ctx.accounts.oracle_feed;
+        feed.authority = ctx.accounts.authority.key();
+        feed.bump = ctx.bumps.oracle_feed;
+        Ok(())
+    }
+
+    /// Publish a new price to an oracle feed. Refused unless built with `mock-oracle`.
+    pub fn publish_oracle_feed(ctx: Context<PublishOracleFeed>, price: u64) -> Result<()> {
+        require!(cfg!(feature = "mock-oracle"), CustomError::MockOracleDisabled);
+        require!(price > 0, CustomError::InvalidAmount);
+        let feed = &mut ctx.accounts.oracle_feed;
+        feed.price = price;
+        feed.publish_time = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// Permissionless crank that samples the configured oracle's latest price into the
+    /// exponentially weighted moving variance of returns. Samples must be at least the config's
+    /// `volatility_sample_interval` apart, so the crank's timing cannot pick which moves count.
+    pub fn update_volatility(ctx: Context<UpdateVolatility>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let config = &ctx.accounts.config;
+        let (price, publish_time) = read_oracle_price(
+            &ctx.accounts.volatility_oracle,
+            current_time,
+            config.max_oracle_staleness,
+        )?;
+        ctx.accounts
+            .volatility_state
+            .record_price(
+                price,
+                publish_time,
+                config.volatility_decay_bps,
+                config.volatility_sample_interval,
+            )
+    }
+
+    /// Adjust a maker's rewards dynamically based on the realized volatility tracked in
+    /// `VolatilityState`, compared against `Config.volatility_threshold`. Permissionless, so
+    /// anyone can bring a maker's multiplier back in line when volatility falls.
+    pub fn adjust_rewards_by_volatility(ctx: Context<AdjustRewards>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let config = &ctx.accounts.config;
+        let volatility_index = ctx
+            .accounts
+            .volatility_state
+            .realized_volatility_bps(current_time, config.max_oracle_staleness)?;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.update(config.emission_rate, current_time)?;
         let staker = &mut ctx.accounts.staker;
-        if volatility_index > 50 {
-            staker.reward_multiplier = 2;
-        } else {
-            staker.reward_multiplier = 1;
-        }
+        // Settle at the old multiplier before the maker's boosted shares change.
+        staker.settle_rewards(reward_pool)?;
+        staker.reward_multiplier = volatility_multiplier(
+            &config.reward_curve,
+            config.volatility_threshold,
+            volatility_index,
+        )?;
+        staker.checkpoint_rewards(reward_pool)?;
         Ok(())
     }
     
-    /// Claim gas fee rebates for high-frequency traders.
+    /// Claim gas fee rebates for high-frequency traders, based on the execution volume of the
+    /// maker's last finalized epoch. Each epoch's rebate can be claimed once.
     pub fn claim_gas_fee_rebate(ctx: Context<ClaimGasRebate>) -> Result<()> {
-        let staker = &ctx.accounts.staker;
-        require!(staker.execution_volume > 100_000, CustomError::NotEligibleForGasRebate);
-        let rebate_amount = staker.execution_volume / 1000;
-        token::transfer(ctx.accounts.into_transfer_gas_rebate_context(), rebate_amount)?;
+        let staker = &mut ctx.accounts.staker;
+        staker.roll_epoch(&ctx.accounts.epoch, &ctx.accounts.config.scoring_weights)?;
+        require!(
+            staker.last_epoch.execution_volume > 100_000 && !staker.last_epoch.gas_rebate_claimed,
+            CustomError::NotEligibleForGasRebate
+        );
+        let rebate_amount = staker.last_epoch.execution_volume / 1000;
+        staker.last_epoch.gas_rebate_claimed = true;
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_gas_rebate_context(&[&seeds]), rebate_amount)?;
         // Optionally, you might want to update the GasFeePool's funds here.
         Ok(())
     }
@@ -201,7 +1062,9 @@ pub mod hfmm_token {
         let provider = &ctx.accounts.liquidity_provider;
         require!(provider.loss_occurred, CustomError::NoLossDetected);
         let insurance_payout = provider.liquidity / 10;
-        token::transfer(ctx.accounts.into_transfer_insurance_context(), insurance_payout)?;
+        let config_key = ctx.accounts.config.key();
+        let seeds = ctx.accounts.config.authority_seeds(&config_key);
+        token::transfer(ctx.accounts.into_transfer_insurance_context(&[&seeds]), insurance_payout)?;
         Ok(())
     }
 }
@@ -212,329 +1075,2377 @@ pub mod hfmm_token {
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
-    #[account(init, payer = user, space = 8 + Config::LEN)]
+    #[account(init, payer = user, space = 8 + Config::LEN, seeds = [CONFIG_SEED], bump)]
     pub config: Account<'info, Config>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct StakeTokens<'info> {
-    #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
-    #[account(mut)]
-    pub staker_token_account: Account<'info, TokenAccount>,
+pub struct InitializeVaults<'info> {
+    #[account(has_one = admin @ CustomError::Unauthorized, has_one = mint)]
+    pub config: Account<'info, Config>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [POOL_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub pool: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [FEE_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub fee_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [GAS_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub gas_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [INSURANCE_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub insurance_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TREASURY_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub treasury: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [REWARD_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LP_REWARD_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub lp_reward_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LP_MINT_SEED, config.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = vault_authority,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [LP_STAKE_VAULT_SEED, config.key().as_ref()],
+        bump,
+        token::mint = lp_mint,
+        token::authority = vault_authority,
+    )]
+    pub lp_stake_vault: Box<Account<'info, TokenAccount>>,
     #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
+    pub admin: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-impl<'info> StakeTokens<'info> {
-    fn into_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        CpiContext::new(
-            self.token_program.to_account_info(),
-            Transfer {
-                from: self.staker_token_account.to_account_info(),
-                to: self.vault.to_account_info(),
-                authority: self.staker.to_account_info(),
-            },
-        )
-    }
+#[derive(Accounts)]
+pub struct InitializePools<'info> {
+    #[account(has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Epoch::LEN,
+        seeds = [EPOCH_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + RewardPool::LEN,
+        seeds = [REWARD_POOL_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + LpRewardPool::LEN,
+        seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FeeRebatePool::LEN,
+        seeds = [FEE_REBATE_POOL_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GasFeePool::LEN,
+        seeds = [GAS_FEE_POOL_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub gas_fee_pool: Account<'info, GasFeePool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + InsurancePool::LEN,
+        seeds = [INSURANCE_POOL_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + VolatilityState::LEN,
+        seeds = [VOLATILITY_STATE_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub volatility_state: Account<'info, VolatilityState>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdatePerformance<'info> {
-    #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
+pub struct AdvanceEpoch<'info> {
+    pub crank: Crank<'info>,
+    #[account(mut, seeds = [EPOCH_SEED, crank.config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(mut, seeds = [FEE_REBATE_POOL_SEED, crank.config.key().as_ref()], bump)]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
 }
 
+/// Accounts shared by the permissionless maintenance instructions, which pay the signing
+/// keeper a bounty for cranking them.
 #[derive(Accounts)]
-pub struct DistributeRewards<'info> {
-    #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
-    #[account(mut)]
-    pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub staker_token_account: Account<'info, TokenAccount>,
+pub struct Crank<'info> {
     pub config: Account<'info, Config>,
+    pub keeper: Signer<'info>,
+    #[account(mut, token::mint = config.mint)]
+    pub keeper_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [TREASURY_SEED, config.key().as_ref()], bump)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> DistributeRewards<'info> {
-    fn into_transfer_to_staker_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        CpiContext::new(
+impl<'info> Crank<'info> {
+    fn into_transfer_bounty_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.vault.to_account_info(),
-                to: self.staker_token_account.to_account_info(),
-                authority: self.staker.to_account_info(),
+                from: self.treasury.to_account_info(),
+                to: self.keeper_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
             },
+            signer_seeds,
         )
     }
+
+    /// Pay the keeper `amount` from the treasury. An underfunded treasury pays what it holds
+    /// rather than blocking the crank.
+    fn pay_flat_bounty(&self, amount: u64) -> Result<()> {
+        let amount = amount.min(self.treasury.amount);
+        if amount == 0 {
+            return Ok(());
+        }
+        let config_key = self.config.key();
+        let seeds = self.config.authority_seeds(&config_key);
+        token::transfer(self.into_transfer_bounty_context(&[&seeds]), amount)?;
+        emit!(KeeperBountyPaid {
+            keeper: self.keeper.key(),
+            amount,
+        });
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
-pub struct ProvideLiquidity<'info> {
+pub struct ExpireLock<'info> {
+    pub config: Account<'info, Config>,
     #[account(mut)]
     pub liquidity_provider: Account<'info, LiquidityProvider>,
-    #[account(mut)]
-    pub provider_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub pool: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
 }
 
-impl<'info> ProvideLiquidity<'info> {
-    fn into_transfer_to_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        CpiContext::new(
-            self.token_program.to_account_info(),
-            Transfer {
-                from: self.provider_token_account.to_account_info(),
-                to: self.pool.to_account_info(),
-                authority: self.liquidity_provider.to_account_info(),
-            },
-        )
-    }
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut, has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawLiquidity<'info> {
-    #[account(mut)]
+pub struct SetRewardCurve<'info> {
+    #[account(mut, has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriorityTiers<'info> {
+    #[account(mut, has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMarketMaker<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + MarketMaker::LEN,
+        seeds = [MARKET_MAKER_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub staker: Account<'info, MarketMaker>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterLiquidityProvider<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LiquidityProvider::LEN,
+        seeds = [LIQUIDITY_PROVIDER_SEED, owner.key().as_ref()],
+        bump,
+    )]
     pub liquidity_provider: Account<'info, LiquidityProvider>,
     #[account(mut)]
-    pub pool: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub staker_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, seeds = [VAULT_SEED, config.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> WithdrawLiquidity<'info> {
-    fn into_transfer_to_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> StakeTokens<'info> {
+    fn into_transfer_to_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.pool.to_account_info(),
-                to: self.user_token_account.to_account_info(),
-                authority: self.liquidity_provider.to_account_info(),
+                from: self.staker_token_account.to_account_info(),
+                to: self.vault.to_account_info(),
+                authority: self.owner.to_account_info(),
             },
         )
     }
 }
 
 #[derive(Accounts)]
-pub struct RequestPriority<'info> {
-    #[account(mut)]
+pub struct RequestUnstake<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
     pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
 }
 
 #[derive(Accounts)]
-pub struct Vote<'info> {
+pub struct CompleteUnstake<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [VAULT_SEED, config.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub voter: Account<'info, Voter>,
+    pub staker_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> CompleteUnstake<'info> {
+    fn into_transfer_to_staker_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.staker_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeReporterRegistry<'info> {
+    #[account(has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ReporterRegistry::LEN,
+        seeds = [REPORTER_REGISTRY_SEED, config.key().as_ref()],
+        bump,
+    )]
+    pub reporter_registry: Account<'info, ReporterRegistry>,
     #[account(mut)]
-    pub proposal: Account<'info, Proposal>,
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-//
-// Contexts for New Enhancements
-//
+#[derive(Accounts)]
+pub struct ManageReporters<'info> {
+    #[account(has_one = admin @ CustomError::Unauthorized)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REPORTER_REGISTRY_SEED, config.key().as_ref()], bump)]
+    pub reporter_registry: Account<'info, ReporterRegistry>,
+    pub admin: Signer<'info>,
+}
 
 #[derive(Accounts)]
-pub struct ClaimFeeRebate<'info> {
+pub struct UpdatePerformance<'info> {
     #[account(mut)]
     pub staker: Account<'info, MarketMaker>,
+    pub reporter: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(seeds = [REPORTER_REGISTRY_SEED, config.key().as_ref()], bump)]
+    pub reporter_registry: Account<'info, ReporterRegistry>,
+    #[account(mut, seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePerformanceWithAttestation<'info> {
     #[account(mut)]
-    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    pub staker: Account<'info, MarketMaker>,
+    pub config: Account<'info, Config>,
+    #[account(seeds = [REPORTER_REGISTRY_SEED, config.key().as_ref()], bump)]
+    pub reporter_registry: Account<'info, ReporterRegistry>,
+    #[account(mut, seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    /// CHECK: Instructions sysvar, checked by address.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeRewards<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    #[account(mut, seeds = [VAULT_SEED, config.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [REWARD_VAULT_SEED, config.key().as_ref()], bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
     #[account(mut)]
     pub staker_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> ClaimFeeRebate<'info> {
-    fn into_transfer_rebate_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
-        CpiContext::new(
+impl<'info> DistributeRewards<'info> {
+    fn into_transfer_to_staker_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.fee_rebate_pool.to_account_info(),
+                from: self.reward_vault.to_account_info(),
                 to: self.staker_token_account.to_account_info(),
-                authority: self.staker.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
             },
+            signer_seeds,
+        )
+    }
+
+    fn into_compound_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reward_vault.to_account_info(),
+                to: self.vault.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
         )
     }
 }
 
 #[derive(Accounts)]
-pub struct LockLiquidity<'info> {
+pub struct FundRewards<'info> {
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, seeds = [REWARD_VAULT_SEED, config.key().as_ref()], bump)]
+    pub reward_vault: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
     #[account(mut)]
-    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct EnableAutoCompound<'info> {
+pub struct FundLpRewards<'info> {
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+    #[account(mut, seeds = [LP_REWARD_VAULT_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_vault: Account<'info, TokenAccount>,
+    pub funder: Signer<'info>,
     #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
+    pub funder_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundLpRewards<'info> {
+    fn into_transfer_to_lp_reward_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.funder_token_account.to_account_info(),
+                to: self.lp_reward_vault.to_account_info(),
+                authority: self.funder.to_account_info(),
+            },
+        )
+    }
+}
+
+impl<'info> FundRewards<'info> {
+    fn into_transfer_to_reward_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.funder_token_account.to_account_info(),
+                to: self.reward_vault.to_account_info(),
+                authority: self.funder.to_account_info(),
+            },
+        )
+    }
 }
 
 #[derive(Accounts)]
-pub struct ClaimLiquidityRewards<'info> {
-    #[account(mut)]
-    pub liquidity_provider: Account<'info, LiquidityProvider>,
+pub struct ProvideLiquidity<'info> {
+    pub owner: Signer<'info>,
     #[account(mut)]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [POOL_SEED, config.key().as_ref()], bump)]
+    pub pool: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [LP_MINT_SEED, config.key().as_ref()], bump)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = lp_mint)]
+    pub provider_lp_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ProvideLiquidity<'info> {
+    fn into_transfer_to_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.provider_token_account.to_account_info(),
+                to: self.pool.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        )
+    }
+
+    fn into_mint_shares_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, MintTo<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.lp_mint.to_account_info(),
+                to: self.provider_lp_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawLiquidity<'info> {
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [POOL_SEED, config.key().as_ref()], bump)]
     pub pool: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub provider_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [LP_MINT_SEED, config.key().as_ref()], bump)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = lp_mint, token::authority = owner)]
+    pub provider_lp_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> ClaimLiquidityRewards<'info> {
-    fn into_transfer_rewards_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> WithdrawLiquidity<'info> {
+    fn into_burn_shares_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
         CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.provider_lp_token_account.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        )
+    }
+
+    fn into_transfer_to_user_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             Transfer {
                 from: self.pool.to_account_info(),
-                to: self.provider_token_account.to_account_info(),
-                authority: self.liquidity_provider.to_account_info(),
+                to: self.user_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
             },
+            signer_seeds,
         )
     }
 }
 
 #[derive(Accounts)]
-pub struct EnforceSlashing<'info> {
-    #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
-    #[account(mut)]
-    pub treasury: Account<'info, TokenAccount>,
+pub struct StakeLpShares<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+    #[account(mut, token::authority = owner)]
+    pub provider_lp_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [LP_STAKE_VAULT_SEED, config.key().as_ref()], bump)]
+    pub lp_stake_vault: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> EnforceSlashing<'info> {
-    fn into_transfer_slash_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> StakeLpShares<'info> {
+    fn into_transfer_to_stake_vault_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.treasury.to_account_info(),
+                from: self.provider_lp_token_account.to_account_info(),
+                to: self.lp_stake_vault.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct UnstakeLpShares<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+    #[account(mut, seeds = [POOL_SEED, config.key().as_ref()], bump)]
+    pub pool: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [LP_MINT_SEED, config.key().as_ref()], bump)]
+    pub lp_mint: Account<'info, Mint>,
+    #[account(mut, token::mint = lp_mint)]
+    pub provider_lp_token_account: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [LP_STAKE_VAULT_SEED, config.key().as_ref()], bump)]
+    pub lp_stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, seeds = [TREASURY_SEED, config.key().as_ref()], bump)]
+    pub treasury: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnstakeLpShares<'info> {
+    fn into_transfer_to_owner_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lp_stake_vault.to_account_info(),
+                to: self.provider_lp_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+
+    fn into_burn_penalty_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Burn<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.lp_stake_vault.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+
+    fn into_transfer_penalty_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.pool.to_account_info(),
                 to: self.treasury.to_account_info(),
-                authority: self.staker.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
             },
+            signer_seeds,
         )
     }
 }
 
 #[derive(Accounts)]
-pub struct EnforceTradeVolume<'info> {
-    #[account(mut)]
+pub struct RequestPriority<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
     pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
 }
 
 #[derive(Accounts)]
-pub struct EnforceStakeCooldown<'info> {
-    #[account(mut)]
+pub struct GetPriorityTier<'info> {
     pub staker: Account<'info, MarketMaker>,
 }
 
 #[derive(Accounts)]
-pub struct AdjustRewards<'info> {
+pub struct OpenPriorityAuction<'info> {
+    #[account(has_one = mint)]
+    pub config: Account<'info, Config>,
+    #[account(seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PriorityAuction::LEN,
+        seeds = [PRIORITY_AUCTION_SEED, config.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump,
+    )]
+    pub auction: Account<'info, PriorityAuction>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        seeds = [AUCTION_ESCROW_SEED, auction.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
     #[account(mut)]
-    pub staker: Account<'info, MarketMaker>,
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimGasRebate<'info> {
-    #[account(mut)]
+pub struct PlacePriorityBid<'info> {
+    #[account(has_one = owner @ CustomError::Unauthorized)]
     pub staker: Account<'info, MarketMaker>,
     #[account(mut)]
-    pub staker_token_account: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PRIORITY_AUCTION_SEED, config.key().as_ref(), &auction.epoch_index.to_le_bytes()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, PriorityAuction>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + PriorityBid::LEN,
+        seeds = [PRIORITY_BID_SEED, auction.key().as_ref(), staker.key().as_ref()],
+        bump,
+    )]
+    pub bid: Account<'info, PriorityBid>,
+    #[account(mut, seeds = [AUCTION_ESCROW_SEED, auction.key().as_ref()], bump)]
+    pub escrow: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub gas_fee_pool: Account<'info, GasFeePool>,
+    pub staker_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-impl<'info> ClaimGasRebate<'info> {
-    fn into_transfer_gas_rebate_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> PlacePriorityBid<'info> {
+    fn into_transfer_to_escrow_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.gas_fee_pool.to_account_info(),
-                to: self.staker_token_account.to_account_info(),
-                authority: self.staker.to_account_info(),
+                from: self.staker_token_account.to_account_info(),
+                to: self.escrow.to_account_info(),
+                authority: self.owner.to_account_info(),
             },
         )
     }
 }
 
 #[derive(Accounts)]
-pub struct ClaimInsurance<'info> {
-    #[account(mut)]
-    pub liquidity_provider: Account<'info, LiquidityProvider>,
-    #[account(mut)]
-    pub insurance_pool: Account<'info, InsurancePool>,
+pub struct RaisePriorityBid<'info> {
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PRIORITY_AUCTION_SEED, config.key().as_ref(), &auction.epoch_index.to_le_bytes()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, PriorityAuction>,
+    #[account(
+        mut,
+        has_one = owner @ CustomError::Unauthorized,
+        has_one = auction,
+        has_one = staker,
+    )]
+    pub bid: Account<'info, PriorityBid>,
+    #[account(mut, seeds = [AUCTION_ESCROW_SEED, auction.key().as_ref()], bump)]
+    pub escrow: Account<'info, TokenAccount>,
     #[account(mut)]
-    pub provider_token_account: Account<'info, TokenAccount>,
+    pub staker_token_account: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
-impl<'info> ClaimInsurance<'info> {
-    fn into_transfer_insurance_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> RaisePriorityBid<'info> {
+    fn into_transfer_to_escrow_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.insurance_pool.to_account_info(),
-                to: self.provider_token_account.to_account_info(),
-                authority: self.liquidity_provider.to_account_info(),
+                from: self.staker_token_account.to_account_info(),
+                to: self.escrow.to_account_info(),
+                authority: self.owner.to_account_info(),
             },
         )
     }
 }
 
-//
-// Data Structures & Account Definitions
-//
+#[derive(Accounts)]
+pub struct SettlePriorityAuction<'info> {
+    pub crank: Crank<'info>,
+    #[account(
+        mut,
+        seeds = [PRIORITY_AUCTION_SEED, crank.config.key().as_ref(), &auction.epoch_index.to_le_bytes()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, PriorityAuction>,
+    #[account(mut, seeds = [AUCTION_ESCROW_SEED, auction.key().as_ref()], bump)]
+    pub escrow: Account<'info, TokenAccount>,
+}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct ConfigParams {
-    pub reward_rate: u64,
-    pub volatility_threshold: u64,
+impl<'info> SettlePriorityAuction<'info> {
+    fn into_transfer_proceeds_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.crank.token_program.to_account_info(),
+            Transfer {
+                from: self.escrow.to_account_info(),
+                to: self.crank.treasury.to_account_info(),
+                authority: self.crank.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
 }
 
-#[account]
-pub struct Config {
-    pub reward_rate: u64,
-    pub volatility_threshold: u64,
+#[derive(Accounts)]
+pub struct ClaimPriorityBid<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(
+        seeds = [PRIORITY_AUCTION_SEED, config.key().as_ref(), &auction.epoch_index.to_le_bytes()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, PriorityAuction>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = auction,
+        has_one = staker,
+        seeds = [PRIORITY_BID_SEED, auction.key().as_ref(), staker.key().as_ref()],
+        bump = bid.bump,
+    )]
+    pub bid: Account<'info, PriorityBid>,
+    #[account(mut, seeds = [AUCTION_ESCROW_SEED, auction.key().as_ref()], bump)]
+    pub escrow: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
-impl Config {
-    const LEN: usize = 8 + 8;
+impl<'info> ClaimPriorityBid<'info> {
+    fn into_refund_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.escrow.to_account_info(),
+                to: self.staker_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
 }
 
-#[account]
-pub struct MarketMaker {
-    pub staked_amount: u64,
+#[derive(Accounts)]
+pub struct VerifyPriority<'info> {
+    #[account(seeds = [MARKET_MAKER_SEED, maker.key().as_ref()], bump = staker.bump)]
+    pub staker: Account<'info, MarketMaker>,
+    /// CHECK: Wallet being verified; only its key is used, to derive `staker`.
+    pub maker: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::LEN,
+        seeds = [PROPOSAL_SEED, config.key().as_ref(), &config.proposal_count.to_le_bytes()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(constraint = staker.voting_key() == voter.key() @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, config.key().as_ref(), &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + VoteRecord::LEN,
+        seeds = [VOTE_RECORD_SEED, proposal.key().as_ref(), staker.key().as_ref()],
+        bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ChangeVote<'info> {
+    #[account(constraint = staker.voting_key() == voter.key() @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub voter: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, config.key().as_ref(), &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        mut,
+        seeds = [VOTE_RECORD_SEED, proposal.key().as_ref(), staker.key().as_ref()],
+        bump = vote_record.bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+}
+
+#[derive(Accounts)]
+pub struct ManageDelegation<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, config.key().as_ref(), &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(
+        mut,
+        seeds = [PROPOSAL_SEED, config.key().as_ref(), &proposal.proposal_id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+//
+// Contexts for New Enhancements
+//
+
+#[derive(Accounts)]
+pub struct ClaimFeeRebate<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(mut, seeds = [FEE_REBATE_POOL_SEED, config.key().as_ref()], bump)]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    #[account(mut, seeds = [FEE_VAULT_SEED, config.key().as_ref()], bump)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimFeeRebate<'info> {
+    fn into_transfer_rebate_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.fee_vault.to_account_info(),
+                to: self.staker_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct DepositFees<'info> {
+    pub depositor: Signer<'info>,
+    #[account(mut)]
+    pub depositor_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    #[account(seeds = [REPORTER_REGISTRY_SEED, config.key().as_ref()], bump)]
+    pub reporter_registry: Account<'info, ReporterRegistry>,
+    #[account(mut, seeds = [FEE_REBATE_POOL_SEED, config.key().as_ref()], bump)]
+    pub fee_rebate_pool: Account<'info, FeeRebatePool>,
+    #[account(mut, seeds = [FEE_VAULT_SEED, config.key().as_ref()], bump)]
+    pub fee_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> DepositFees<'info> {
+    fn into_transfer_fees_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.depositor_token_account.to_account_info(),
+                to: self.fee_vault.to_account_info(),
+                authority: self.depositor.to_account_info(),
+            },
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct LockLiquidity<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct EnableAutoCompound<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLiquidityRewards<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [LP_REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_pool: Account<'info, LpRewardPool>,
+    #[account(mut, seeds = [LP_REWARD_VAULT_SEED, config.key().as_ref()], bump)]
+    pub lp_reward_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimLiquidityRewards<'info> {
+    fn into_transfer_rewards_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lp_reward_vault.to_account_info(),
+                to: self.provider_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct EnforceSlashing<'info> {
+    pub crank: Crank<'info>,
+    #[account(mut)]
+    pub staker: Account<'info, MarketMaker>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, crank.config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(mut, seeds = [VAULT_SEED, crank.config.key().as_ref()], bump)]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+impl<'info> EnforceSlashing<'info> {
+    fn into_transfer_slash_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.crank.token_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.crank.treasury.to_account_info(),
+                authority: self.crank.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+
+    fn into_transfer_bounty_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.crank.token_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.crank.keeper_token_account.to_account_info(),
+                authority: self.crank.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct EnforceTradeVolume<'info> {
+    #[account(mut)]
+    pub staker: Account<'info, MarketMaker>,
+}
+
+#[derive(Accounts)]
+pub struct EnforceStakeCooldown<'info> {
+    #[account(mut)]
+    pub staker: Account<'info, MarketMaker>,
+}
+
+#[derive(Accounts)]
+pub struct AdjustRewards<'info> {
+    #[account(mut)]
+    pub staker: Account<'info, MarketMaker>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [REWARD_POOL_SEED, config.key().as_ref()], bump)]
+    pub reward_pool: Account<'info, RewardPool>,
+    #[account(seeds = [VOLATILITY_STATE_SEED, config.key().as_ref()], bump)]
+    pub volatility_state: Account<'info, VolatilityState>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVolatility<'info> {
+    pub config: Account<'info, Config>,
+    /// CHECK: Either this program's `OracleFeed` or a Pyth price account; `read_oracle_price`
+    /// checks the owner and layout.
+    #[account(address = config.volatility_oracle @ CustomError::InvalidOracle)]
+    pub volatility_oracle: UncheckedAccount<'info>,
+    #[account(mut, seeds = [VOLATILITY_STATE_SEED, config.key().as_ref()], bump)]
+    pub volatility_state: Account<'info, VolatilityState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeOracleFeed<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + OracleFeed::LEN,
+        seeds = [ORACLE_FEED_SEED, authority.key().as_ref()],
+        bump,
+    )]
+    pub oracle_feed: Account<'info, OracleFeed>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PublishOracleFeed<'info> {
+    #[account(mut, has_one = authority @ CustomError::Unauthorized)]
+    pub oracle_feed: Account<'info, OracleFeed>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimGasRebate<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub staker: Account<'info, MarketMaker>,
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub staker_token_account: Account<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    #[account(seeds = [EPOCH_SEED, config.key().as_ref()], bump)]
+    pub epoch: Account<'info, Epoch>,
+    #[account(mut, seeds = [GAS_FEE_POOL_SEED, config.key().as_ref()], bump)]
+    pub gas_fee_pool: Account<'info, GasFeePool>,
+    #[account(mut, seeds = [GAS_VAULT_SEED, config.key().as_ref()], bump)]
+    pub gas_vault: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimGasRebate<'info> {
+    fn into_transfer_gas_rebate_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.gas_vault.to_account_info(),
+                to: self.staker_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimInsurance<'info> {
+    #[account(mut, has_one = owner @ CustomError::Unauthorized)]
+    pub liquidity_provider: Account<'info, LiquidityProvider>,
+    pub owner: Signer<'info>,
+    pub config: Account<'info, Config>,
+    #[account(mut, seeds = [INSURANCE_POOL_SEED, config.key().as_ref()], bump)]
+    pub insurance_pool: Account<'info, InsurancePool>,
+    #[account(mut, seeds = [INSURANCE_VAULT_SEED, config.key().as_ref()], bump)]
+    pub insurance_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    /// CHECK: PDA used only as the token authority of the program's vaults.
+    #[account(seeds = [AUTHORITY_SEED, config.key().as_ref()], bump = config.authority_bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimInsurance<'info> {
+    fn into_transfer_insurance_context<'a, 'b, 'c>(
+        &self,
+        signer_seeds: &'a [&'b [&'c [u8]]],
+    ) -> CpiContext<'a, 'b, 'c, 'info, Transfer<'info>> {
+        CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.insurance_vault.to_account_info(),
+                to: self.provider_token_account.to_account_info(),
+                authority: self.vault_authority.to_account_info(),
+            },
+            signer_seeds,
+        )
+    }
+}
+
+//
+// Data Structures & Account Definitions
+//
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub reward_rate: u64,
+    pub volatility_threshold: u64,
+    pub unbonding_period: i64,   // Seconds between `request_unstake` and `complete_unstake`.
+    pub emission_rate: u64,      // Staking reward tokens emitted per second.
+    pub epoch_duration: i64,     // Minimum length of a performance epoch, in seconds.
+    pub scoring_weights: ScoringWeights,
+    pub early_exit_penalty_bps: u64, // Charged on early withdrawals of locked liquidity; 0 refuses them.
+    pub lp_reward_rate: u64,     // LP reward per unit of liquidity per second, scaled by `REWARD_PRECISION`.
+    pub quorum_votes: u64,       // Minimum total votes (including abstentions) for a proposal to pass.
+    pub approval_threshold_bps: u64, // Required share of for votes among for + against.
+    pub execution_delay: i64,    // Timelock between a proposal passing and its execution, in seconds.
+    pub inactivity_period: i64,  // Seconds without trading before a maker can be slashed.
+    pub slash_bps: u64,          // Share of stake slashed per inactivity period.
+    pub keeper_bounty_bps: u64,  // Share of each slash paid to the keeper that cranks it.
+    pub keeper_flat_fee: u64,    // Treasury-funded bounty for epoch and lock-expiry cranks.
+    pub volatility_oracle: Pubkey, // `OracleFeed` or Pyth price account sampled by `update_volatility`.
+    pub max_oracle_staleness: i64, // Oldest acceptable oracle publish or volatility sample, in seconds.
+    pub volatility_decay_bps: u64, // EWMA weight kept by the previous variance on each sample.
+    pub priority_stake_threshold: u64, // Minimum stake to hold a priority ticket.
+    pub priority_ticket_slots: u64, // How long a priority ticket lasts once issued or renewed.
+    pub auction_slots: u8,       // Priority slots auctioned per epoch; 0 leaves tickets open to all stakers.
+    pub auction_duration: i64,   // Bidding window of each priority auction, in seconds.
+    pub min_voting_period: i64,  // Shortest voting window a proposal may set, in seconds.
+    pub volatility_sample_interval: i64, // Minimum seconds between volatility samples.
+}
+
+impl ConfigParams {
+    const LEN: usize = 8 + 8 + 8 + 8 + 8 + ScoringWeights::LEN + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+        + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8;
+
+    fn validate(&self) -> Result<()> {
+        // `reward_rate` is used as a divisor when distributing rewards.
+        require!(self.reward_rate > 0, CustomError::InvalidConfig);
+        require!(self.unbonding_period >= 0, CustomError::InvalidConfig);
+        require!(self.epoch_duration > 0, CustomError::InvalidConfig);
+        require!(self.early_exit_penalty_bps <= BPS_DENOMINATOR, CustomError::InvalidConfig);
+        require!(self.approval_threshold_bps <= BPS_DENOMINATOR, CustomError::InvalidConfig);
+        require!(self.execution_delay >= 0, CustomError::InvalidConfig);
+        require!(self.inactivity_period > 0, CustomError::InvalidConfig);
+        require!(self.slash_bps <= BPS_DENOMINATOR, CustomError::InvalidConfig);
+        require!(self.keeper_bounty_bps <= BPS_DENOMINATOR, CustomError::InvalidConfig);
+        require!(self.max_oracle_staleness > 0, CustomError::InvalidConfig);
+        require!(self.volatility_decay_bps < BPS_DENOMINATOR, CustomError::InvalidConfig);
+        require!(self.priority_ticket_slots > 0, CustomError::InvalidConfig);
+        require!(
+            self.auction_slots as usize <= PriorityAuction::MAX_SLOTS,
+            CustomError::InvalidConfig
+        );
+        require!(self.auction_duration > 0, CustomError::InvalidConfig);
+        require!(self.min_voting_period > 0, CustomError::InvalidConfig);
+        require!(self.volatility_sample_interval > 0, CustomError::InvalidConfig);
+        // Every sample must be fresh, so the interval cannot outlast the staleness bound.
+        require!(
+            self.volatility_sample_interval <= self.max_oracle_staleness,
+            CustomError::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+    pub authority_bump: u8,
+    pub reward_rate: u64,
+    pub volatility_threshold: u64,
+    pub unbonding_period: i64,
+    pub emission_rate: u64,
+    pub epoch_duration: i64,
+    pub scoring_weights: ScoringWeights,
+    pub early_exit_penalty_bps: u64,
+    pub lp_reward_rate: u64,
+    pub quorum_votes: u64,
+    pub approval_threshold_bps: u64,
+    pub execution_delay: i64,
+    pub inactivity_period: i64,
+    pub slash_bps: u64,
+    pub keeper_bounty_bps: u64,
+    pub keeper_flat_fee: u64,
+    pub volatility_oracle: Pubkey,
+    pub max_oracle_staleness: i64,
+    pub volatility_decay_bps: u64,
+    pub priority_stake_threshold: u64,
+    pub priority_ticket_slots: u64,
+    pub auction_slots: u8,
+    pub auction_duration: i64,
+    pub min_voting_period: i64,
+    pub volatility_sample_interval: i64,
+    pub config_version: u64,     // Bumped whenever `ConfigParams` are applied.
+    pub proposal_count: u64,     // Seeds the next proposal's PDA.
+    pub reward_curve: Vec<RewardCurvePoint>, // Set with `set_reward_curve`.
+    pub priority_tiers: Vec<PriorityTier>, // Set with `set_priority_tiers`.
+}
+
+impl Config {
+    const MAX_REWARD_CURVE_POINTS: usize = 8;
+    const MAX_PRIORITY_TIERS: usize = 4;
+    const LEN: usize = 32 + 32 + 1 + 1 + ConfigParams::LEN + 8 + 8
+        + 4 + Self::MAX_REWARD_CURVE_POINTS * RewardCurvePoint::LEN
+        + 4 + Self::MAX_PRIORITY_TIERS * PriorityTier::LEN;
+
+    /// Signer seeds for the vault authority PDA.
+    fn authority_seeds<'a>(&'a self, config_key: &'a Pubkey) -> [&'a [u8]; 3] {
+        [AUTHORITY_SEED, config_key.as_ref(), std::slice::from_ref(&self.authority_bump)]
+    }
+
+    fn params(&self) -> ConfigParams {
+        ConfigParams {
+            reward_rate: self.reward_rate,
+            volatility_threshold: self.volatility_threshold,
+            unbonding_period: self.unbonding_period,
+            emission_rate: self.emission_rate,
+            epoch_duration: self.epoch_duration,
+            scoring_weights: self.scoring_weights,
+            early_exit_penalty_bps: self.early_exit_penalty_bps,
+            lp_reward_rate: self.lp_reward_rate,
+            quorum_votes: self.quorum_votes,
+            approval_threshold_bps: self.approval_threshold_bps,
+            execution_delay: self.execution_delay,
+            inactivity_period: self.inactivity_period,
+            slash_bps: self.slash_bps,
+            keeper_bounty_bps: self.keeper_bounty_bps,
+            keeper_flat_fee: self.keeper_flat_fee,
+            volatility_oracle: self.volatility_oracle,
+            max_oracle_staleness: self.max_oracle_staleness,
+            volatility_decay_bps: self.volatility_decay_bps,
+            priority_stake_threshold: self.priority_stake_threshold,
+            priority_ticket_slots: self.priority_ticket_slots,
+            auction_slots: self.auction_slots,
+            auction_duration: self.auction_duration,
+            min_voting_period: self.min_voting_period,
+            volatility_sample_interval: self.volatility_sample_interval,
+        }
+    }
+
+    fn apply(&mut self, params: &ConfigParams) {
+        self.config_version = self.config_version.checked_add(1).unwrap();
+        self.reward_rate = params.reward_rate;
+        self.volatility_threshold = params.volatility_threshold;
+        self.unbonding_period = params.unbonding_period;
+        self.emission_rate = params.emission_rate;
+        self.epoch_duration = params.epoch_duration;
+        self.scoring_weights = params.scoring_weights;
+        self.early_exit_penalty_bps = params.early_exit_penalty_bps;
+        self.lp_reward_rate = params.lp_reward_rate;
+        self.quorum_votes = params.quorum_votes;
+        self.approval_threshold_bps = params.approval_threshold_bps;
+        self.execution_delay = params.execution_delay;
+        self.inactivity_period = params.inactivity_period;
+        self.slash_bps = params.slash_bps;
+        self.keeper_bounty_bps = params.keeper_bounty_bps;
+        self.keeper_flat_fee = params.keeper_flat_fee;
+        self.volatility_oracle = params.volatility_oracle;
+        self.max_oracle_staleness = params.max_oracle_staleness;
+        self.volatility_decay_bps = params.volatility_decay_bps;
+        self.priority_stake_threshold = params.priority_stake_threshold;
+        self.priority_ticket_slots = params.priority_ticket_slots;
+        self.auction_slots = params.auction_slots;
+        self.auction_duration = params.auction_duration;
+        self.min_voting_period = params.min_voting_period;
+        self.volatility_sample_interval = params.volatility_sample_interval;
+    }
+}
+
+#[account]
+pub struct MarketMaker {
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub staked_amount: u64,
+    pub execution_volume: u64,
+    pub spread_efficiency: u64,
+    pub order_flow: u64,
+    pub priority_expiry_slot: u64, // Priority ticket is valid before this slot; 0 if none.
+    pub auto_compound: bool,
+    pub last_trade_time: i64,
+    pub last_stake_time: i64,    // For cooldown enforcement.
+    pub reward_multiplier: u64,  // For dynamic risk-based rewards, in basis points.
+    pub unbonding: Vec<UnbondingEntry>,
+    pub reward_shares: u64,      // Boosted stake currently registered in the `RewardPool`.
+    pub reward_debt: u128,       // `reward_shares × acc_reward_per_share` at the last checkpoint.
+    pub pending_rewards: u64,    // Settled staking rewards not yet paid out.
+    pub epoch_index: u64,        // Epoch the live performance counters belong to.
+    pub last_epoch: EpochPerformance,
+    pub performance_nonce: u64,  // Nonce of the last accepted performance report.
+    pub stake_checkpoints: Vec<StakeCheckpoint>, // Stake history used for governance snapshots.
+    pub delegate: Option<Pubkey>, // Votes with this maker's stake instead of the owner.
+    pub last_slashed_at: i64,
+    pub priority_tier: Option<u8>, // Index into `Config.priority_tiers` of the current ticket.
+    pub boosted_volume: u64,     // Live execution volume weighted by the tier's rebate boost.
+}
+
+impl MarketMaker {
+    const MAX_UNBONDING_ENTRIES: usize = 8;
+    const MAX_STAKE_CHECKPOINTS: usize = 8;
+    const LEN: usize = 32 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 8
+        + 4 + Self::MAX_UNBONDING_ENTRIES * UnbondingEntry::LEN
+        + 8 + 16 + 8
+        + 8 + EpochPerformance::LEN
+        + 8
+        + 4 + Self::MAX_STAKE_CHECKPOINTS * StakeCheckpoint::LEN
+        + 33
+        + 8
+        + 2
+        + 8;
+
+    /// Whether this maker holds a priority ticket that is valid at `slot`.
+    pub fn has_priority_access(&self, slot: u64) -> bool {
+        slot < self.priority_expiry_slot
+    }
+
+    /// Fee rebate boost of the maker's priority tier, or 0 without a valid ticket at `slot`.
+    fn rebate_boost_bps(&self, config: &Config, slot: u64) -> u64 {
+        match self.priority_tier {
+            Some(tier) if self.has_priority_access(slot) => config
+                .priority_tiers
+                .get(tier as usize)
+                .map_or(0, |tier| tier.rebate_boost_bps),
+            _ => 0,
+        }
+    }
+
+    /// Issue, or renew, a priority ticket valid until `expiry_slot` at the highest tier the
+    /// stake supports.
+    fn issue_priority_ticket(&mut self, config: &Config, expiry_slot: u64) {
+        self.priority_expiry_slot = expiry_slot;
+        self.priority_tier = priority_tier_for(&config.priority_tiers, self.staked_amount);
+        emit!(PriorityTicketIssued {
+            owner: self.owner,
+            expiry_slot: self.priority_expiry_slot,
+            tier: self.priority_tier,
+        });
+    }
+
+    /// After stake decreases, revoke the priority ticket if stake has fallen below the
+    /// config's threshold, or otherwise drop it to the highest tier the stake still supports.
+    fn refresh_priority(&mut self, config: &Config) {
+        if self.priority_expiry_slot == 0 {
+            return;
+        }
+        if self.staked_amount < config.priority_stake_threshold {
+            self.priority_expiry_slot = 0;
+            self.priority_tier = None;
+            emit!(PriorityTicketRevoked { owner: self.owner });
+        } else {
+            // `None` orders below every tier, so this only ever downgrades.
+            self.priority_tier = self
+                .priority_tier
+                .min(priority_tier_for(&config.priority_tiers, self.staked_amount));
+        }
+    }
+
+    /// The key allowed to cast this maker's governance votes.
+    fn voting_key(&self) -> Pubkey {
+        self.delegate.unwrap_or(self.owner)
+    }
+
+    /// Record `staked_amount` as of `now`. When the history is full the two oldest checkpoints
+    /// are merged keeping the smaller amount, so old snapshots can under-count voting power
+    /// but never inflate it.
+    fn record_stake_checkpoint(&mut self, now: i64) {
+        let amount = self.staked_amount;
+        if let Some(last) = self.stake_checkpoints.last_mut() {
+            if last.timestamp == now {
+                last.amount = amount;
+                return;
+            }
+        }
+        if self.stake_checkpoints.len() == Self::MAX_STAKE_CHECKPOINTS {
+            let oldest = self.stake_checkpoints.remove(0);
+            let next = &mut self.stake_checkpoints[0];
+            next.timestamp = oldest.timestamp;
+            next.amount = next.amount.min(oldest.amount);
+        }
+        self.stake_checkpoints.push(StakeCheckpoint { timestamp: now, amount });
+    }
+
+    /// Stake held strictly before `timestamp`, so stake added in the same second a proposal
+    /// is created does not count towards it.
+    fn stake_at(&self, timestamp: i64) -> u64 {
+        self.stake_checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.timestamp < timestamp)
+            .map_or(0, |checkpoint| checkpoint.amount)
+    }
+
+    /// Weighted score of the live performance counters.
+    fn live_score(&self, weights: &ScoringWeights) -> Result<u64> {
+        compute_score(weights, self.execution_volume, self.spread_efficiency, self.order_flow)
+            .ok_or_else(|| error!(CustomError::MathOverflow))
+    }
+
+    /// If the live counters belong to an epoch that has since been finalized, snapshot them
+    /// into `last_epoch` and start counting from zero for the current epoch.
+    fn roll_epoch(&mut self, epoch: &Epoch, weights: &ScoringWeights) -> Result<()> {
+        if self.epoch_index >= epoch.index {
+            return Ok(());
+        }
+        self.last_epoch = EpochPerformance {
+            epoch_index: self.epoch_index,
+            execution_volume: self.execution_volume,
+            boosted_volume: self.boosted_volume,
+            spread_efficiency: self.spread_efficiency,
+            order_flow: self.order_flow,
+            score: self.live_score(weights)?,
+            rewarded: false,
+            rebate_claimed: false,
+            gas_rebate_claimed: false,
+        };
+        self.execution_volume = 0;
+        self.boosted_volume = 0;
+        self.spread_efficiency = 0;
+        self.order_flow = 0;
+        self.epoch_index = epoch.index;
+        Ok(())
+    }
+
+    /// Apply a reporter's performance update to the live counters. `nonce` must be greater
+    /// than the last accepted one so a report cannot be replayed.
+    /// Volume is also accumulated boosted by the tier's rebate boost held at report time, which
+    /// weights the maker's share of the epoch's fee rebates.
+    fn record_performance(
+        &mut self,
+        epoch: &mut Epoch,
+        config: &Config,
+        execution_volume: u64,
+        spread_efficiency: u64,
+        order_flow: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        require!(nonce > self.performance_nonce, CustomError::StaleNonce);
+        self.performance_nonce = nonce;
+        self.roll_epoch(epoch, &config.scoring_weights)?;
+        let boost_bps = self.rebate_boost_bps(config, Clock::get()?.slot);
+        let boosted_volume =
+            apply_multiplier(execution_volume, BPS_DENOMINATOR.checked_add(boost_bps).unwrap())?;
+        self.boosted_volume = self.boosted_volume.checked_add(boosted_volume).unwrap();
+        epoch.total_boosted_volume = epoch.total_boosted_volume.checked_add(boosted_volume).unwrap();
+        self.execution_volume = self.execution_volume.checked_add(execution_volume).unwrap();
+        self.spread_efficiency = self.spread_efficiency.checked_add(spread_efficiency).unwrap();
+        self.order_flow = self.order_flow.checked_add(order_flow).unwrap();
+        self.last_trade_time = Clock::get()?.unix_timestamp;
+        epoch.total_volume = epoch.total_volume.checked_add(execution_volume).unwrap();
+        Ok(())
+    }
+
+    /// Move rewards accrued since the last checkpoint into `pending_rewards`.
+    /// `pool` must already be updated to the current time.
+    fn settle_rewards(&mut self, pool: &RewardPool) -> Result<()> {
+        let accumulated = (self.reward_shares as u128)
+            .checked_mul(pool.acc_reward_per_share)
+            .unwrap()
+            .checked_div(REWARD_PRECISION)
+            .unwrap();
+        let accrued = accumulated.checked_sub(self.reward_debt).unwrap();
+        self.reward_debt = accumulated;
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(u64::try_from(accrued).map_err(|_| CustomError::MathOverflow)?)
+            .unwrap();
+        Ok(())
+    }
+
+    /// Re-register this maker's shares with the pool after its stake or multiplier changed and
+    /// reset the reward debt so already-settled rewards are never paid twice. Shares are the
+    /// staked amount boosted by `reward_multiplier`.
+    fn checkpoint_rewards(&mut self, pool: &mut RewardPool) -> Result<()> {
+        let shares = apply_multiplier(self.staked_amount, self.reward_multiplier)?;
+        pool.total_shares = pool
+            .total_shares
+            .checked_sub(self.reward_shares)
+            .unwrap()
+            .checked_add(shares)
+            .unwrap();
+        self.reward_shares = shares;
+        self.reward_debt = (shares as u128)
+            .checked_mul(pool.acc_reward_per_share)
+            .unwrap()
+            .checked_div(REWARD_PRECISION)
+            .unwrap();
+        Ok(())
+    }
+}
+
+/// LP shares minted for depositing `amount` into a pool holding `pool_assets` against `supply`
+/// outstanding shares, priced with `LP_VIRTUAL_OFFSET` on both sides. The first deposit into
+/// an empty pool mints 1:1.
+pub fn shares_for_deposit(amount: u64, pool_assets: u64, supply: u64) -> Result<u64> {
+    let shares = (amount as u128)
+        .checked_mul(supply as u128 + LP_VIRTUAL_OFFSET as u128)
+        .unwrap()
+        .checked_div(pool_assets as u128 + LP_VIRTUAL_OFFSET as u128)
+        .unwrap();
+    u64::try_from(shares).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+/// Pool assets redeemable for `shares` at the current share price, rounded down and never more
+/// than the pool holds.
+pub fn assets_for_shares(shares: u64, pool_assets: u64, supply: u64) -> Result<u64> {
+    require!(supply > 0, CustomError::InsufficientFunds);
+    let assets = (shares as u128)
+        .checked_mul(pool_assets as u128 + LP_VIRTUAL_OFFSET as u128)
+        .unwrap()
+        .checked_div(supply as u128 + LP_VIRTUAL_OFFSET as u128)
+        .unwrap();
+    let assets = u64::try_from(assets).map_err(|_| error!(CustomError::MathOverflow))?;
+    Ok(assets.min(pool_assets))
+}
+
+/// Scale `amount` by a multiplier expressed in basis points (10_000 = 1x).
+fn apply_multiplier(amount: u64, multiplier_bps: u64) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(multiplier_bps as u128)
+        .unwrap()
+        .checked_div(BPS_DENOMINATOR as u128)
+        .unwrap();
+    u64::try_from(scaled).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+/// `apply_multiplier`, rounded up instead of down.
+fn apply_multiplier_ceil(amount: u64, multiplier_bps: u64) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(multiplier_bps as u128)
+        .unwrap()
+        .div_ceil(BPS_DENOMINATOR as u128);
+    u64::try_from(scaled).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+/// Per-metric weights (in basis points) and optional caps used to score maker performance.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ScoringWeights {
+    pub execution_weight_bps: u32,
+    pub spread_weight_bps: u32,
+    pub order_flow_weight_bps: u32,
+    pub execution_cap: Option<u64>,
+    pub spread_cap: Option<u64>,
+    pub order_flow_cap: Option<u64>,
+}
+
+impl ScoringWeights {
+    const LEN: usize = 4 + 4 + 4 + 9 + 9 + 9;
+}
+
+/// Weighted performance score: each metric is clamped to its cap, if any, multiplied by its
+/// weight in basis points, and the sum is divided by `BPS_DENOMINATOR`. Returns `None` on
+/// overflow. Pure so off-chain simulators compute exactly the number the program does.
+pub fn compute_score(
+    weights: &ScoringWeights,
+    execution_volume: u64,
+    spread_efficiency: u64,
+    order_flow: u64,
+) -> Option<u64> {
+    let weighted = |value: u64, cap: Option<u64>, weight_bps: u32| {
+        (cap.map_or(value, |cap| value.min(cap)) as u128).checked_mul(weight_bps as u128)
+    };
+    let total = weighted(execution_volume, weights.execution_cap, weights.execution_weight_bps)?
+        .checked_add(weighted(spread_efficiency, weights.spread_cap, weights.spread_weight_bps)?)?
+        .checked_add(weighted(order_flow, weights.order_flow_cap, weights.order_flow_weight_bps)?)?;
+    u64::try_from(total / BPS_DENOMINATOR as u128).ok()
+}
+
+/// A breakpoint of the volatility reward curve.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RewardCurvePoint {
+    pub volatility_bps: u64,
+    pub multiplier_bps: u64,
+}
+
+impl RewardCurvePoint {
+    const LEN: usize = 8 + 8;
+}
+
+fn validate_reward_curve(breakpoints: &[RewardCurvePoint]) -> Result<()> {
+    require!(
+        breakpoints.len() <= Config::MAX_REWARD_CURVE_POINTS,
+        CustomError::InvalidRewardCurve
+    );
+    require!(
+        breakpoints.iter().all(|point| point.multiplier_bps > 0),
+        CustomError::InvalidRewardCurve
+    );
+    require!(
+        breakpoints.windows(2).all(|pair| {
+            pair[0].volatility_bps < pair[1].volatility_bps
+                && pair[0].multiplier_bps <= pair[1].multiplier_bps
+        }),
+        CustomError::InvalidRewardCurve
+    );
+    Ok(())
+}
+
+/// Reward multiplier, in basis points, for a realized volatility. The curve is interpolated
+/// linearly between breakpoints and held flat beyond its ends. Without a curve, volatility
+/// above `volatility_threshold` earns 2x and anything else 1x.
+pub fn volatility_multiplier(
+    curve: &[RewardCurvePoint],
+    volatility_threshold: u64,
+    volatility_bps: u64,
+) -> Result<u64> {
+    let (first, last) = match (curve.first(), curve.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ if volatility_bps > volatility_threshold => return Ok(2 * BPS_DENOMINATOR),
+        _ => return Ok(BPS_DENOMINATOR),
+    };
+    if volatility_bps <= first.volatility_bps {
+        return Ok(first.multiplier_bps);
+    }
+    if volatility_bps >= last.volatility_bps {
+        return Ok(last.multiplier_bps);
+    }
+    let upper = curve.iter().position(|point| point.volatility_bps >= volatility_bps).unwrap();
+    let (low, high) = (curve[upper - 1], curve[upper]);
+    let rise = (high.multiplier_bps - low.multiplier_bps) as u128;
+    let run = (high.volatility_bps - low.volatility_bps) as u128;
+    let offset = (volatility_bps - low.volatility_bps) as u128;
+    let multiplier = low.multiplier_bps as u128 + rise * offset / run;
+    u64::try_from(multiplier).map_err(|_| error!(CustomError::MathOverflow))
+}
+
+/// A priority access level and the privileges it grants. Matching engines read these
+/// alongside the maker's tier index.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriorityTier {
+    pub stake_threshold: u64,
+    pub rate_limit_budget: u32,  // Orders per slot the venue should accept from the maker.
+    pub rebate_boost_bps: u64,   // Extra fee rebate, on top of the maker's volume share.
+    pub priority_weight: u32,    // Relative queue priority among ticket holders.
+}
+
+impl PriorityTier {
+    const LEN: usize = 8 + 4 + 8 + 4;
+}
+
+/// Index of the highest tier whose stake threshold `staked_amount` meets, if any.
+pub fn priority_tier_for(tiers: &[PriorityTier], staked_amount: u64) -> Option<u8> {
+    tiers
+        .iter()
+        .rposition(|tier| staked_amount >= tier.stake_threshold)
+        .map(|index| index as u8)
+}
+
+/// One epoch's auction for a limited number of priority slots.
+#[account]
+pub struct PriorityAuction {
+    pub epoch_index: u64,
+    pub bump: u8,
+    pub end_time: i64,
+    pub slots: u8,
+    pub settled: bool,
+    pub ticket_expiry_slot: u64,  // Set at settlement; winners' tickets expire here.
+    pub leaders: Vec<AuctionBid>, // Current top bids, highest first; the winners once settled.
+}
+
+impl PriorityAuction {
+    const MAX_SLOTS: usize = 8;
+    const LEN: usize = 8 + 1 + 8 + 1 + 1 + 8 + 4 + Self::MAX_SLOTS * AuctionBid::LEN;
+
+    /// Record `bidder`'s total bid of `amount`. A new bidder must fill an open slot or beat the
+    /// lowest leader, who is displaced; ties keep the earlier bid.
+    fn record_bid(&mut self, bidder: Pubkey, amount: u64) -> Result<()> {
+        if let Some(leader) = self.leaders.iter_mut().find(|leader| leader.bidder == bidder) {
+            leader.amount = amount;
+        } else if self.leaders.len() < self.slots as usize {
+            self.leaders.push(AuctionBid { bidder, amount, forfeited: false });
+        } else {
+            let lowest = self.leaders.last_mut().unwrap();
+            require!(amount > lowest.amount, CustomError::BidTooLow);
+            *lowest = AuctionBid { bidder, amount, forfeited: false };
+        }
+        self.leaders.sort_by_key(|leader| std::cmp::Reverse(leader.amount));
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AuctionBid {
+    pub bidder: Pubkey, // The bidding `MarketMaker` account.
+    pub amount: u64,
+    pub forfeited: bool, // Set at settlement if the bidder fell below the stake threshold.
+}
+
+impl AuctionBid {
+    const LEN: usize = 32 + 8 + 1;
+}
+
+/// A maker's escrowed bid in a priority auction, closed when claimed.
+#[account]
+pub struct PriorityBid {
+    pub auction: Pubkey,
+    pub staker: Pubkey,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub amount: u64,
+}
+
+impl PriorityBid {
+    const LEN: usize = 32 + 32 + 32 + 1 + 8;
+}
+
+/// A maker's performance over one finalized epoch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct EpochPerformance {
+    pub epoch_index: u64,
     pub execution_volume: u64,
+    pub boosted_volume: u64,     // Volume weighted by the priority tier held when reported.
     pub spread_efficiency: u64,
     pub order_flow: u64,
-    pub has_priority_access: bool,
-    pub auto_compound: bool,
-    pub last_trade_time: i64,
-    pub last_stake_time: i64,    // For cooldown enforcement.
-    pub reward_multiplier: u64,  // For dynamic risk-based rewards.
+    pub score: u64,
+    pub rewarded: bool,          // Set once `distribute_rewards` has paid this epoch.
+    pub rebate_claimed: bool,    // Set once `claim_fee_rebate` has paid this epoch.
+    pub gas_rebate_claimed: bool, // Set once `claim_gas_fee_rebate` has paid this epoch.
+}
+
+impl EpochPerformance {
+    const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StakeCheckpoint {
+    pub timestamp: i64,
+    pub amount: u64,
+}
+
+impl StakeCheckpoint {
+    const LEN: usize = 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UnbondingEntry {
+    pub amount: u64,
+    pub release_time: i64,
+}
+
+impl UnbondingEntry {
+    const LEN: usize = 8 + 8;
 }
 
 #[account]
 pub struct LiquidityProvider {
-    pub liquidity: u64,
-    pub lock_duration: u64,
-    pub reward_multiplier: u64,
+    pub owner: Pubkey,
+    pub bump: u8,
+    pub liquidity: u64,          // LP shares staked in the program's custody.
+    pub lock_start: i64,
+    pub unlock_at: i64,          // Liquidity cannot be withdrawn penalty-free before this time.
+    pub reward_multiplier: u64,  // Lock bonus, in basis points.
     pub last_deposit_time: i64,
     pub reward_balance: u64,
     pub loss_occurred: bool,     // For insurance payout.
+    pub last_accrual_time: i64,  // When `reward_balance` was last settled.
+}
+
+impl LiquidityProvider {
+    const LEN: usize = 32 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8;
+
+    /// Credit `reward_balance` with `liquidity × seconds × reward_multiplier × lp_reward_rate`
+    /// since the last accrual. The lock bonus only applies to time before `unlock_at`.
+    fn accrue_rewards(
+        &mut self,
+        pool: &mut LpRewardPool,
+        lp_reward_rate: u64,
+        current_time: i64,
+    ) -> Result<()> {
+        if current_time <= self.last_accrual_time {
+            return Ok(());
+        }
+        let boosted_until = self.unlock_at.clamp(self.last_accrual_time, current_time);
+        let boosted_seconds = (boosted_until - self.last_accrual_time) as u128;
+        let base_seconds = (current_time - boosted_until) as u128;
+        // Liquidity-seconds weighted in basis points.
+        let weighted_seconds = boosted_seconds
+            .checked_mul(self.reward_multiplier as u128)
+            .unwrap()
+            .checked_add(base_seconds.checked_mul(BPS_DENOMINATOR as u128).unwrap())
+            .unwrap();
+        let reward = (self.liquidity as u128)
+            .checked_mul(weighted_seconds)
+            .unwrap()
+            .checked_mul(lp_reward_rate as u128)
+            .unwrap()
+            .checked_div(BPS_DENOMINATOR as u128 * REWARD_PRECISION)
+            .unwrap()
+            .min(pool.unemitted as u128) as u64;
+        pool.unemitted = pool.unemitted.checked_sub(reward).unwrap();
+        self.reward_balance = self.reward_balance.checked_add(reward).unwrap();
+        self.last_accrual_time = current_time;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct ReporterRegistry {
+    pub reporters: Vec<Pubkey>,
+}
+
+impl ReporterRegistry {
+    const MAX_REPORTERS: usize = 16;
+    const LEN: usize = 4 + Self::MAX_REPORTERS * 32;
+
+    fn is_reporter(&self, key: &Pubkey) -> bool {
+        self.reporters.contains(key)
+    }
+}
+
+/// Price published by an off-chain oracle authority. Only readable with the `mock-oracle`
+/// feature.
+#[account]
+pub struct OracleFeed {
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub price: u64,
+    pub publish_time: i64,
+}
+
+impl OracleFeed {
+    const LEN: usize = 32 + 1 + 8 + 8;
+
+    /// The published price, provided it is no older than `max_staleness` seconds.
+    #[cfg(feature = "mock-oracle")]
+    fn fresh_price(&self, now: i64, max_staleness: i64) -> Result<u64> {
+        require!(
+            self.publish_time > 0 && now.saturating_sub(self.publish_time) <= max_staleness,
+            CustomError::StaleOracle
+        );
+        Ok(self.price)
+    }
+}
+
+/// Aggregate price read from a Pyth (v2) price account.
+pub struct PythPrice {
+    pub price: i64,              // In units of 10^expo; the exponent cancels out of returns.
+    pub publish_time: i64,
+    pub trading: bool,           // Aggregate status is `Trading`.
+}
+
+impl PythPrice {
+    const MAGIC: u32 = 0xa1b2c3d4;
+    const PRICE_ACCOUNT_TYPE: u32 = 3;
+    const STATUS_TRADING: u32 = 1;
+    // Offsets into the price account.
+    const ACCOUNT_TYPE_OFFSET: usize = 8;
+    const TIMESTAMP_OFFSET: usize = 96;
+    const AGG_PRICE_OFFSET: usize = 208;
+    const AGG_STATUS_OFFSET: usize = 224;
+
+    fn parse(data: &[u8]) -> Result<Self> {
+        let read_u32 = |offset: usize| -> Result<u32> {
+            let bytes = data.get(offset..offset + 4).ok_or(CustomError::InvalidOracle)?;
+            Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+        };
+        let read_i64 = |offset: usize| -> Result<i64> {
+            let bytes = data.get(offset..offset + 8).ok_or(CustomError::InvalidOracle)?;
+            Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
+        };
+        require!(
+            read_u32(0)? == Self::MAGIC
+                && read_u32(Self::ACCOUNT_TYPE_OFFSET)? == Self::PRICE_ACCOUNT_TYPE,
+            CustomError::InvalidOracle
+        );
+        Ok(Self {
+            price: read_i64(Self::AGG_PRICE_OFFSET)?,
+            publish_time: read_i64(Self::TIMESTAMP_OFFSET)?,
+            trading: read_u32(Self::AGG_STATUS_OFFSET)? == Self::STATUS_TRADING,
+        })
+    }
+
+    /// The aggregate price, provided it is trading, positive and no older than `max_staleness`
+    /// seconds.
+    fn fresh_price(&self, now: i64, max_staleness: i64) -> Result<u64> {
+        require!(
+            self.trading && now.saturating_sub(self.publish_time) <= max_staleness,
+            CustomError::StaleOracle
+        );
+        require!(self.price > 0, CustomError::InvalidOracle);
+        Ok(self.price as u64)
+    }
+}
+
+/// Latest price and publish time of the volatility oracle, which is a price account owned by a
+/// Pyth oracle program or, with the `mock-oracle` feature, an `OracleFeed` owned by this program.
+fn read_oracle_price(oracle: &AccountInfo, now: i64, max_staleness: i64) -> Result<(u64, i64)> {
+    #[cfg(feature = "mock-oracle")]
+    if oracle.owner == &crate::ID {
+        let feed = OracleFeed::try_deserialize(&mut &oracle.try_borrow_data()?[..])?;
+        return Ok((feed.fresh_price(now, max_staleness)?, feed.publish_time));
+    }
+    require!(
+        oracle.owner == &pyth_mainnet::ID || oracle.owner == &pyth_devnet::ID,
+        CustomError::InvalidOracle
+    );
+    let feed = PythPrice::parse(&oracle.try_borrow_data()?)?;
+    Ok((feed.fresh_price(now, max_staleness)?, feed.publish_time))
+}
+
+/// Exponentially weighted moving variance of oracle price returns.
+#[account]
+pub struct VolatilityState {
+    pub last_price: u64,
+    pub last_publish_time: i64,  // Publish time of the last sampled oracle price.
+    pub variance: u128,          // EWMA of squared returns, in `RETURN_PRECISION`².
+    pub sample_count: u64,       // Prices sampled so far.
+}
+
+impl VolatilityState {
+    const LEN: usize = 8 + 8 + 16 + 8;
+
+    /// Fold the return since the last sampled price into the variance:
+    /// `variance = decay × variance + (1 − decay) × return²`. The first return seeds it.
+    /// Samples must be at least `interval` seconds apart, and each squared return is scaled by
+    /// `interval / elapsed`, so the variance is per interval however far apart samples are.
+    fn record_price(
+        &mut self,
+        price: u64,
+        publish_time: i64,
+        decay_bps: u64,
+        interval: i64,
+    ) -> Result<()> {
+        require!(publish_time > self.last_publish_time, CustomError::StaleOracle);
+        if self.sample_count > 0 {
+            let elapsed = publish_time - self.last_publish_time;
+            require!(elapsed >= interval, CustomError::VolatilitySampleTooSoon);
+            let last_price = self.last_price as u128;
+            let change = (price as u128).abs_diff(last_price);
+            let price_return = change.checked_mul(RETURN_PRECISION).unwrap() / last_price;
+            let squared = price_return
+                .checked_mul(price_return)
+                .unwrap()
+                .checked_mul(interval as u128)
+                .unwrap()
+                / elapsed as u128;
+            self.variance = if self.sample_count == 1 {
+                squared
+            } else {
+                self.variance
+                    .checked_mul(decay_bps as u128)
+                    .unwrap()
+                    .checked_add(squared.checked_mul((BPS_DENOMINATOR - decay_bps) as u128).unwrap())
+                    .unwrap()
+                    / BPS_DENOMINATOR as u128
+            };
+        }
+        self.last_price = price;
+        self.last_publish_time = publish_time;
+        self.sample_count = self.sample_count.saturating_add(1);
+        Ok(())
+    }
+
+    /// Realized volatility (the square root of the variance) in basis points per sample
+    /// interval.
+    /// Requires at least one return and a sample no older than `max_staleness` seconds.
+    fn realized_volatility_bps(&self, now: i64, max_staleness: i64) -> Result<u64> {
+        require!(
+            self.sample_count >= 2 && now.saturating_sub(self.last_publish_time) <= max_staleness,
+            CustomError::StaleOracle
+        );
+        let volatility = integer_sqrt(self.variance) * BPS_DENOMINATOR as u128 / RETURN_PRECISION;
+        u64::try_from(volatility).map_err(|_| error!(CustomError::MathOverflow))
+    }
+}
+
+/// Floor of the square root of `n`.
+fn integer_sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = (x >> 1) + (x & 1);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// The bytes a reporter signs to attest to a performance update:
+/// `staker || execution_volume || spread_efficiency || order_flow || nonce`, integers little-endian.
+pub fn performance_report_message(
+    staker: &Pubkey,
+    execution_volume: u64,
+    spread_efficiency: u64,
+    order_flow: u64,
+    nonce: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 8 * 4);
+    message.extend_from_slice(staker.as_ref());
+    message.extend_from_slice(&execution_volume.to_le_bytes());
+    message.extend_from_slice(&spread_efficiency.to_le_bytes());
+    message.extend_from_slice(&order_flow.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message
+}
+
+/// Check that the instruction before the current one is an ed25519 program instruction
+/// verifying a single signature over `expected_message`, and return the signer's key.
+fn verify_ed25519_attestation(instructions: &AccountInfo, expected_message: &[u8]) -> Result<Pubkey> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, CustomError::MissingAttestation);
+    let ix = load_instruction_at_checked((current_index - 1) as usize, instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, CustomError::MissingAttestation);
+
+    // Layout: num_signatures (u8), padding (u8), then one 14-byte offsets struct per signature.
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, CustomError::InvalidAttestation);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    // The signature, key and message must all live in the ed25519 instruction itself.
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        CustomError::InvalidAttestation
+    );
+    let public_key_offset = read_u16(6) as usize;
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(CustomError::InvalidAttestation)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(CustomError::InvalidAttestation)?;
+    require!(message == expected_message, CustomError::InvalidAttestation);
+    Ok(Pubkey::new_from_array(public_key.try_into().unwrap()))
+}
+
+#[account]
+pub struct Epoch {
+    pub index: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_volume: u64,       // Live execution volume of the current epoch across all makers.
+    pub total_boosted_volume: u64, // Live boosted volume of the current epoch across all makers.
+}
+
+impl Epoch {
+    const LEN: usize = 8 + 8 + 8 + 8 + 8;
+}
+
+#[account]
+pub struct RewardPool {
+    pub acc_reward_per_share: u128, // Scaled by `REWARD_PRECISION`.
+    pub total_shares: u64,
+    pub last_update_time: i64,
+    pub unemitted: u64,             // Funded reward vault tokens not yet emitted or paid as bonuses.
+}
+
+impl RewardPool {
+    const LEN: usize = 16 + 8 + 8 + 8;
+
+    /// Accrue `emission_rate` tokens per second since the last update across all shares, up to
+    /// the funds left in the reward vault.
+    fn update(&mut self, emission_rate: u64, current_time: i64) -> Result<()> {
+        if current_time <= self.last_update_time {
+            return Ok(());
+        }
+        if self.total_shares > 0 {
+            let elapsed = (current_time - self.last_update_time) as u128;
+            let emitted = elapsed
+                .checked_mul(emission_rate as u128)
+                .unwrap()
+                .min(self.unemitted as u128);
+            self.unemitted = self.unemitted.checked_sub(emitted as u64).unwrap();
+            let increment = emitted
+                .checked_mul(REWARD_PRECISION)
+                .unwrap()
+                .checked_div(self.total_shares as u128)
+                .unwrap();
+            self.acc_reward_per_share = self.acc_reward_per_share.checked_add(increment).unwrap();
+        }
+        self.last_update_time = current_time;
+        Ok(())
+    }
+}
+
+/// Funded budget of liquidity rewards, held in the LP reward vault.
+#[account]
+pub struct LpRewardPool {
+    pub unemitted: u64,          // LP reward vault tokens not yet accrued to any provider.
+}
+
+impl LpRewardPool {
+    const LEN: usize = 8;
 }
 
 #[account]
 pub struct FeeRebatePool {
-    pub total_fees: u64,
-    pub total_execution_volume: u64,
+    pub total_fees: u64,              // Fees deposited during the current epoch.
+    pub total_execution_volume: u64,  // Volume reported by depositors during the current epoch.
+    pub last_epoch_index: u64,        // Finalized epoch whose fees are claimable.
+    pub last_epoch_fees: u64,
+    pub last_epoch_volume: u64,       // Rebate denominator of the finalized epoch.
+    pub last_epoch_reported_volume: u64, // Depositor-reported volume of the finalized epoch.
+    pub last_epoch_maker_fees: u64,   // Share of the finalized epoch's fees owed to makers.
+    pub last_epoch_boosted_volume: u64, // Summed boosted maker volume of the finalized epoch.
+    pub last_epoch_unclaimed: u64,    // Carried into the next epoch's fees if not claimed in time.
+}
+
+impl FeeRebatePool {
+    const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+
+    /// Finalize the current epoch's deposits as `closing_index`, making them claimable. Makers
+    /// are owed the fees in proportion of `epoch_volume`, their summed volume, to the larger of
+    /// it and the volume depositors reported, so fees earned on volume no maker reported are
+    /// not paid out. Each maker's share of those is weighted by `epoch_boosted_volume`, so
+    /// boosted rebates never add up to more than the epoch's fees. Fees nobody claimed from
+    /// the previous epoch are added to the new claimable total.
+    fn roll(
+        &mut self,
+        closing_index: u64,
+        epoch_volume: u64,
+        epoch_boosted_volume: u64,
+    ) -> Result<()> {
+        let fees = self.total_fees.checked_add(self.last_epoch_unclaimed).unwrap();
+        self.last_epoch_index = closing_index;
+        self.last_epoch_fees = fees;
+        self.last_epoch_volume = epoch_volume.max(self.total_execution_volume);
+        self.last_epoch_reported_volume = self.total_execution_volume;
+        self.last_epoch_maker_fees = if self.last_epoch_volume == 0 {
+            0
+        } else {
+            let maker_fees = (fees as u128)
+                .checked_mul(epoch_volume as u128)
+                .unwrap()
+                .checked_div(self.last_epoch_volume as u128)
+                .unwrap();
+            u64::try_from(maker_fees).map_err(|_| error!(CustomError::MathOverflow))?
+        };
+        self.last_epoch_boosted_volume = epoch_boosted_volume;
+        self.last_epoch_unclaimed = fees;
+        self.total_fees = 0;
+        self.total_execution_volume = 0;
+        Ok(())
+    }
+
+    /// Rebate owed for `boosted_volume` traded during the last finalized epoch: its share of
+    /// the epoch's boosted volume, applied to the fees owed to makers.
+    fn rebate_for(&self, boosted_volume: u64) -> Result<u64> {
+        if self.last_epoch_boosted_volume == 0 {
+            return Ok(0);
+        }
+        let rebate = (self.last_epoch_maker_fees as u128)
+            .checked_mul(boosted_volume as u128)
+            .unwrap()
+            .checked_div(self.last_epoch_boosted_volume as u128)
+            .unwrap();
+        let rebate = u64::try_from(rebate).map_err(|_| error!(CustomError::MathOverflow))?;
+        Ok(rebate.min(self.last_epoch_unclaimed))
+    }
 }
 
 #[account]
@@ -542,20 +3453,207 @@ pub struct GasFeePool {
     pub total_funds: u64,
 }
 
+impl GasFeePool {
+    const LEN: usize = 8;
+}
+
 #[account]
 pub struct InsurancePool {
     pub total_funds: u64,
 }
 
+impl InsurancePool {
+    const LEN: usize = 8;
+}
+
 #[account]
 pub struct Proposal {
     pub proposal_id: u64,
-    pub votes: u64,
+    pub proposer: Pubkey,
+    pub bump: u8,
+    pub description_hash: [u8; 32], // Hash of the off-chain proposal text.
+    pub action: ProposalAction,
+    pub voting_start: i64,
+    pub voting_end: i64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+    pub status: ProposalStatus,
+    pub executable_at: i64,      // Set when the proposal passes.
+    pub config_version: u64,     // `Config.config_version` when the proposal was created.
+}
+
+impl Proposal {
+    const LEN: usize = 8 + 32 + 1 + 32 + ProposalAction::LEN + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+
+    fn require_voting_open(&self, now: i64) -> Result<()> {
+        require!(self.status == ProposalStatus::Active, CustomError::ProposalNotActive);
+        require!(now < self.voting_end, CustomError::VotingClosed);
+        Ok(())
+    }
+
+    fn tally_mut(&mut self, choice: VoteChoice) -> &mut u64 {
+        match choice {
+            VoteChoice::For => &mut self.votes_for,
+            VoteChoice::Against => &mut self.votes_against,
+            VoteChoice::Abstain => &mut self.votes_abstain,
+        }
+    }
+
+    fn add_votes(&mut self, choice: VoteChoice, weight: u64) {
+        let tally = self.tally_mut(choice);
+        *tally = tally.checked_add(weight).unwrap();
+    }
+
+    fn remove_votes(&mut self, choice: VoteChoice, weight: u64) {
+        let tally = self.tally_mut(choice);
+        *tally = tally.checked_sub(weight).unwrap();
+    }
+
+    fn passes(&self, quorum_votes: u64, approval_threshold_bps: u64) -> bool {
+        let votes_for = self.votes_for as u128;
+        let decisive = votes_for + self.votes_against as u128;
+        let turnout = decisive + self.votes_abstain as u128;
+        votes_for > 0
+            && turnout >= quorum_votes as u128
+            && votes_for * BPS_DENOMINATOR as u128 >= decisive * approval_threshold_bps as u128
+    }
+}
+
+/// What a proposal does when executed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ProposalAction {
+    UpdateConfig { params: ConfigParams },
+}
+
+impl ProposalAction {
+    const LEN: usize = 1 + ConfigParams::LEN;
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::UpdateConfig { params } => params.validate(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Active,
+    Succeeded,
+    Defeated,
+    Executed,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VoteChoice {
+    For,
+    Against,
+    Abstain,
+}
+
+/// One market maker's vote on one proposal.
 #[account]
-pub struct Voter {
-    pub token_balance: u64,
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub staker: Pubkey,
+    pub voter: Pubkey,           // Owner or delegate that last cast this vote.
+    pub bump: u8,
+    pub choice: VoteChoice,
+    pub weight: u64,             // Stake snapshotted at proposal creation.
+}
+
+impl VoteRecord {
+    const LEN: usize = 32 + 32 + 32 + 1 + 1 + 8;
+}
+
+//
+// Events
+//
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_params: ConfigParams,
+    pub new_params: ConfigParams,
+}
+
+#[event]
+pub struct EpochAdvanced {
+    pub index: u64,
+    pub total_volume: u64,       // Summed maker volume.
+    pub reported_volume: u64,    // Volume reported with the epoch's fee deposits.
+}
+
+#[event]
+pub struct RewardCurveUpdated {
+    pub admin: Pubkey,
+    pub breakpoints: Vec<RewardCurvePoint>,
+}
+
+#[event]
+pub struct PriorityTiersUpdated {
+    pub admin: Pubkey,
+    pub tiers: Vec<PriorityTier>,
+}
+
+#[event]
+pub struct PriorityAuctionOpened {
+    pub epoch_index: u64,
+    pub end_time: i64,
+    pub slots: u8,
+}
+
+#[event]
+pub struct PriorityBidPlaced {
+    pub epoch_index: u64,
+    pub owner: Pubkey,
+    pub amount: u64, // The bid's new total.
+}
+
+#[event]
+pub struct PriorityAuctionSettled {
+    pub epoch_index: u64,
+    pub winners: Vec<Pubkey>,
+    pub proceeds: u64,
+}
+
+#[event]
+pub struct PriorityTicketIssued {
+    pub owner: Pubkey,
+    pub expiry_slot: u64,
+    pub tier: Option<u8>,
+}
+
+#[event]
+pub struct PriorityTicketRevoked {
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct KeeperBountyPaid {
+    pub keeper: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub voting_end: i64,
+}
+
+#[event]
+pub struct ProposalFinalized {
+    pub proposal_id: u64,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub proposal_id: u64,
 }
 
 //
@@ -584,4 +3682,131 @@ pub enum CustomError {
     NotEligibleForGasRebate,
     #[msg("No loss detected; insurance payout is not available.")]
     NoLossDetected,
+    #[msg("Signer is not authorized to perform this action.")]
+    Unauthorized,
+    #[msg("Invalid configuration parameters.")]
+    InvalidConfig,
+    #[msg("Amount must be greater than zero.")]
+    InvalidAmount,
+    #[msg("Too many pending unbonding entries.")]
+    TooManyUnbondingEntries,
+    #[msg("No unbonded stake is ready to withdraw.")]
+    NothingToUnstake,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("Liquidity is locked.")]
+    LiquidityLocked,
+    #[msg("A liquidity lock can be extended but not shortened.")]
+    LockCannotBeShortened,
+    #[msg("The current epoch has not ended yet.")]
+    EpochNotEnded,
+    #[msg("Signer is not a registered performance reporter.")]
+    UnauthorizedReporter,
+    #[msg("Reporter is already registered.")]
+    ReporterAlreadyRegistered,
+    #[msg("Reporter registry is full.")]
+    ReporterRegistryFull,
+    #[msg("Performance report nonce has already been used.")]
+    StaleNonce,
+    #[msg("Missing ed25519 attestation instruction.")]
+    MissingAttestation,
+    #[msg("Ed25519 attestation does not match the performance report.")]
+    InvalidAttestation,
+    #[msg("Voting period is shorter than the configured minimum.")]
+    InvalidVotingPeriod,
+    #[msg("Proposal is not open for voting.")]
+    ProposalNotActive,
+    #[msg("The voting window for this proposal has closed.")]
+    VotingClosed,
+    #[msg("The voting window for this proposal has not ended yet.")]
+    VotingNotEnded,
+    #[msg("Only proposals that passed can be executed.")]
+    ProposalNotSucceeded,
+    #[msg("The proposal's execution timelock has not elapsed.")]
+    TimelockNotElapsed,
+    #[msg("Votes cannot be delegated to the market maker's own owner.")]
+    InvalidDelegate,
+    #[msg("Market maker is not eligible for slashing yet.")]
+    SlashingNotDue,
+    #[msg("Liquidity lock has not expired.")]
+    LockNotExpired,
+    #[msg("Oracle account does not match the configured volatility oracle.")]
+    InvalidOracle,
+    #[msg("Oracle data is stale.")]
+    StaleOracle,
+    #[msg("Reward curve breakpoints must be increasing in volatility with non-decreasing multipliers.")]
+    InvalidRewardCurve,
+    #[msg("Priority tiers must have strictly increasing stake thresholds.")]
+    InvalidPriorityTiers,
+    #[msg("The market maker does not hold a valid priority ticket.")]
+    NoPriorityTicket,
+    #[msg("Priority tickets are only issued through the priority auction.")]
+    PriorityAuctioned,
+    #[msg("Priority slots are not being auctioned.")]
+    PriorityNotAuctioned,
+    #[msg("Bidding on this priority auction has closed.")]
+    AuctionClosed,
+    #[msg("The priority auction has not ended yet.")]
+    AuctionNotEnded,
+    #[msg("The priority auction has not been settled yet.")]
+    AuctionNotSettled,
+    #[msg("The priority auction has already been settled.")]
+    AuctionSettled,
+    #[msg("The bid does not beat the lowest winning bid.")]
+    BidTooLow,
+    #[msg("Volatility samples must be at least the configured interval apart.")]
+    VolatilitySampleTooSoon,
+    #[msg("Local oracle feeds require a build with the mock-oracle feature.")]
+    MockOracleDisabled,
+    #[msg("The config has changed since the proposal was created.")]
+    StaleProposal,
+    #[msg("Remaining accounts must be the auction leaders' market maker accounts, in order.")]
+    LeaderAccountMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Pyth v2 price account with the given aggregate price, status and publish time.
+    fn pyth_price_fixture(price: i64, status: u32, timestamp: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 240];
+        data[0..4].copy_from_slice(&PythPrice::MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&2u32.to_le_bytes()); // Version.
+        data[8..12].copy_from_slice(&PythPrice::PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[20..24].copy_from_slice(&(-8i32).to_le_bytes()); // Exponent.
+        data[96..104].copy_from_slice(&timestamp.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[216..224].copy_from_slice(&1_000u64.to_le_bytes()); // Confidence.
+        data[224..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn parses_pyth_price_account() {
+        let data = pyth_price_fixture(6_512_345_678_900, 1, 1_700_000_000);
+        let feed = PythPrice::parse(&data).unwrap();
+        assert_eq!(feed.price, 6_512_345_678_900);
+        assert_eq!(feed.publish_time, 1_700_000_000);
+        assert!(feed.trading);
+        assert_eq!(feed.fresh_price(1_700_000_030, 60).unwrap(), 6_512_345_678_900);
+        assert!(feed.fresh_price(1_700_000_061, 60).is_err());
+    }
+
+    #[test]
+    fn rejects_halted_or_malformed_pyth_accounts() {
+        let halted = PythPrice::parse(&pyth_price_fixture(100, 2, 1_700_000_000)).unwrap();
+        assert!(!halted.trading);
+        assert!(halted.fresh_price(1_700_000_000, 60).is_err());
+
+        let mut wrong_magic = pyth_price_fixture(100, 1, 1_700_000_000);
+        wrong_magic[0] ^= 0xff;
+        assert!(PythPrice::parse(&wrong_magic).is_err());
+
+        let mut product_account = pyth_price_fixture(100, 1, 1_700_000_000);
+        product_account[8..12].copy_from_slice(&2u32.to_le_bytes());
+        assert!(PythPrice::parse(&product_account).is_err());
+
+        assert!(PythPrice::parse(&pyth_price_fixture(100, 1, 1_700_000_000)[..200]).is_err());
+    }
 }
@@ -4,256 +4,1607 @@ describe("HFMM Token Program", () => {
   const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
     "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
   );
+  // Replace with the HFMMT mint from your test environment.
+  const hfmmtMint = new web3.PublicKey("HfmmtMintPublicKey");
+
+  // Program-derived addresses. Everything except the config is seeded from the config's key.
+  const [configPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    pg.program.programId
+  );
+  const findPda = (seed: string) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from(seed), configPda.toBuffer()],
+      pg.program.programId
+    )[0];
+  const vaultAuthority = findPda("authority");
+  const vaultPda = findPda("vault");
+  const poolPda = findPda("pool");
+  const feeVaultPda = findPda("fee_vault");
+  const gasVaultPda = findPda("gas_vault");
+  const insuranceVaultPda = findPda("insurance_vault");
+  const treasuryPda = findPda("treasury");
+  const rewardVaultPda = findPda("reward_vault");
+  const lpRewardVaultPda = findPda("lp_reward_vault");
+  const lpMintPda = findPda("lp_mint");
+  const lpStakeVaultPda = findPda("lp_stake_vault");
+  const feeRebatePoolPda = findPda("fee_rebate_pool");
+  const gasFeePoolPda = findPda("gas_fee_pool");
+  const insurancePoolPda = findPda("insurance_pool");
+  const rewardPoolPda = findPda("reward_pool");
+  const lpRewardPoolPda = findPda("lp_reward_pool");
+  const epochPda = findPda("epoch");
+  const reporterRegistryPda = findPda("reporter_registry");
+  const volatilityStatePda = findPda("volatility_state");
+  const findProposalPda = (proposalId: BN) =>
+    web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("proposal"), configPda.toBuffer(), proposalId.toArrayLike(Buffer, "le", 8)],
+      pg.program.programId
+    )[0];
+  // Per-user accounts are seeded from the owner's wallet.
+  const [marketMakerPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("market_maker"), pg.wallet.publicKey.toBuffer()],
+    pg.program.programId
+  );
+  const [liquidityProviderPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("liquidity_provider"), pg.wallet.publicKey.toBuffer()],
+    pg.program.programId
+  );
+  // Local oracle feed published by the wallet, standing in for an external price oracle.
+  const [oracleFeedPda] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("oracle_feed"), pg.wallet.publicKey.toBuffer()],
+    pg.program.programId
+  );
+
+  // Baseline config passed to `initialize`; later tests override individual fields.
+  const configParams = {
+    rewardRate: new BN(100),
+    volatilityThreshold: new BN(50),
+    unbondingPeriod: new BN(604800), // 7 days.
+    emissionRate: new BN(10), // Staking reward tokens per second.
+    epochDuration: new BN(2), // Short epochs so the tests can roll them over.
+    // Score = 2×execution + 3×spread efficiency + 1×order flow, uncapped.
+    scoringWeights: {
+      executionWeightBps: 20_000,
+      spreadWeightBps: 30_000,
+      orderFlowWeightBps: 10_000,
+      executionCap: null,
+      spreadCap: null,
+      orderFlowCap: null,
+    },
+    earlyExitPenaltyBps: new BN(0), // Locked liquidity cannot leave early.
+    lpRewardRate: new BN(1_000_000_000), // 0.001 reward per unit of liquidity per second.
+    quorumVotes: new BN(1),
+    approvalThresholdBps: new BN(5_000), // Simple majority of for + against.
+    executionDelay: new BN(0), // No timelock, so passed proposals can execute in the tests.
+    inactivityPeriod: new BN(2_592_000), // 30 days.
+    slashBps: new BN(1_000), // 10% of stake per inactivity period.
+    keeperBountyBps: new BN(1_000), // Keepers receive 10% of each slash...
+    keeperFlatFee: new BN(10), // ...or 10 tokens from the treasury for other cranks.
+    volatilityOracle: oracleFeedPda,
+    maxOracleStaleness: new BN(60), // Seconds.
+    volatilityDecayBps: new BN(9_400), // Each sample keeps 94% of the previous variance.
+    priorityStakeThreshold: new BN(300),
+    priorityTicketSlots: new BN(9_000), // About an hour.
+    auctionSlots: 0, // Tickets stay open to every staker until the auction test.
+    auctionDuration: new BN(3), // Seconds of bidding per auction.
+    minVotingPeriod: new BN(1), // Short enough for the governance tests to wait out.
+    volatilitySampleInterval: new BN(1), // Seconds between volatility samples.
+  };
+
+  // Accounts shared by the permissionless keeper cranks. The wallet acts as the keeper.
+  const crankAccounts = {
+    config: configPda,
+    keeper: pg.wallet.publicKey,
+    keeperTokenAccount: new web3.PublicKey("KeeperTokenAccountPublicKey"),
+    treasury: treasuryPda,
+    vaultAuthority: vaultAuthority,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  };
+
+  // The mock DEX in `mock-dex/`, deployed alongside this program, checks priority tickets over
+  // CPI. Its `submit_priority_order` instruction is sent raw: the 8-byte Anchor discriminator,
+  // sha256("global:submit_priority_order")[..8], and no arguments.
+  const MOCK_DEX_PROGRAM_ID = new web3.PublicKey("DSY9vNdarQiTmuJpd7ob9FnNSFBAozoitCtVmn3dmbPJ");
+  const simulateSubmitPriorityOrder = async () => {
+    const tx = new web3.Transaction().add(
+      new web3.TransactionInstruction({
+        programId: MOCK_DEX_PROGRAM_ID,
+        keys: [
+          { pubkey: marketMakerPda, isSigner: false, isWritable: false },
+          { pubkey: pg.wallet.publicKey, isSigner: true, isWritable: false },
+          { pubkey: pg.program.programId, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.from([187, 162, 175, 214, 167, 246, 65, 87]),
+      })
+    );
+    tx.feePayer = pg.wallet.publicKey;
+    return (await pg.connection.simulateTransaction(tx)).value;
+  };
 
   it("initializes the config", async () => {
-    const configKp = new web3.Keypair();
-    const rewardRate = new BN(100);
-    const volatilityThreshold = new BN(50);
     // Pass a single object with the expected keys
     const txHash = await pg.program.methods
-      .initialize({ rewardRate, volatilityThreshold })
+      .initialize(configParams)
       .accounts({
-        config: configKp.publicKey,
+        config: configPda,
+        mint: hfmmtMint,
+        vaultAuthority: vaultAuthority,
         user: pg.wallet.publicKey,
         systemProgram: web3.SystemProgram.programId,
       })
-      .signers([configKp])
       .rpc();
     console.log(`Config initialized. Tx: ${txHash}`);
 
-    const configAccount = await pg.program.account.config.fetch(configKp.publicKey);
+    const configAccount = await pg.program.account.config.fetch(configPda);
     // Assert that on-chain values match our inputs.
+    assert(configAccount.rewardRate.eq(configParams.rewardRate));
+    assert(configAccount.volatilityThreshold.eq(configParams.volatilityThreshold));
+    assert(configAccount.unbondingPeriod.eq(configParams.unbondingPeriod));
+    assert(configAccount.admin.equals(pg.wallet.publicKey));
+    assert(configAccount.mint.equals(hfmmtMint));
+  });
+
+  it("initializes the vaults", async () => {
+    const txHash = await pg.program.methods
+      .initializeVaults()
+      .accounts({
+        config: configPda,
+        mint: hfmmtMint,
+        vaultAuthority: vaultAuthority,
+        vault: vaultPda,
+        pool: poolPda,
+        feeVault: feeVaultPda,
+        gasVault: gasVaultPda,
+        insuranceVault: insuranceVaultPda,
+        treasury: treasuryPda,
+        rewardVault: rewardVaultPda,
+        lpRewardVault: lpRewardVaultPda,
+        lpMint: lpMintPda,
+        lpStakeVault: lpStakeVaultPda,
+        admin: pg.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+    console.log(`Vaults initialized. Tx: ${txHash}`);
+
+    // Every vault must be owned by the program's vault authority.
+    for (const vault of [vaultPda, poolPda, feeVaultPda, gasVaultPda, insuranceVaultPda, treasuryPda, rewardVaultPda, lpRewardVaultPda]) {
+      const info = await pg.connection.getParsedAccountInfo(vault);
+      const owner = (info.value.data as any).parsed.info.owner;
+      assert.equal(owner, vaultAuthority.toBase58());
+    }
+    const stakeVaultInfo = await pg.connection.getParsedAccountInfo(lpStakeVaultPda);
+    assert.equal((stakeVaultInfo.value.data as any).parsed.info.mint, lpMintPda.toBase58());
+  });
+
+  it("initializes the pools", async () => {
+    const txHash = await pg.program.methods
+      .initializePools()
+      .accounts({
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        lpRewardPool: lpRewardPoolPda,
+        epoch: epochPda,
+        feeRebatePool: feeRebatePoolPda,
+        gasFeePool: gasFeePoolPda,
+        insurancePool: insurancePoolPda,
+        volatilityState: volatilityStatePda,
+        admin: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Pools initialized. Tx: ${txHash}`);
+  });
+
+  it("funds staking rewards", async () => {
+    const amount = new BN(100_000);
+    const txHash = await pg.program.methods
+      .fundRewards(amount)
+      .accounts({
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        rewardVault: rewardVaultPda,
+        funder: pg.wallet.publicKey,
+        funderTokenAccount: new web3.PublicKey("FunderTokenAccountPublicKey"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Fund rewards. Tx: ${txHash}`);
+
+    // Emissions draw down the funded budget rather than stakers' principal.
+    const rewardPool = await pg.program.account.rewardPool.fetch(rewardPoolPda);
+    assert(rewardPool.unemitted.eq(amount));
+  });
+
+  it("funds liquidity rewards", async () => {
+    const amount = new BN(100_000);
+    const txHash = await pg.program.methods
+      .fundLpRewards(amount)
+      .accounts({
+        config: configPda,
+        lpRewardPool: lpRewardPoolPda,
+        lpRewardVault: lpRewardVaultPda,
+        funder: pg.wallet.publicKey,
+        funderTokenAccount: new web3.PublicKey("FunderTokenAccountPublicKey"),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Fund liquidity rewards. Tx: ${txHash}`);
+
+    // Liquidity rewards accrue against their own budget, never the treasury.
+    const lpRewardPool = await pg.program.account.lpRewardPool.fetch(lpRewardPoolPda);
+    assert(lpRewardPool.unemitted.eq(amount));
+  });
+
+  it("updates the config", async () => {
+    const rewardRate = new BN(200);
+    const volatilityThreshold = new BN(75);
+    // A zero unbonding period lets the unstake tests below complete immediately.
+    const unbondingPeriod = new BN(0);
+    const txHash = await pg.program.methods
+      .updateConfig({ ...configParams, rewardRate, volatilityThreshold, unbondingPeriod })
+      .accounts({
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        admin: pg.wallet.publicKey,
+      })
+      .rpc();
+    console.log(`Config updated. Tx: ${txHash}`);
+
+    const configAccount = await pg.program.account.config.fetch(configPda);
     assert(configAccount.rewardRate.eq(rewardRate));
     assert(configAccount.volatilityThreshold.eq(volatilityThreshold));
+    assert(configAccount.unbondingPeriod.eq(unbondingPeriod));
+  });
+
+  it("updates the scoring weights", async () => {
+    const scoringWeights = { ...configParams.scoringWeights, executionCap: new BN(1_000_000) };
+    const txHash = await pg.program.methods
+      .updateConfig({ ...configParams, unbondingPeriod: new BN(0), scoringWeights })
+      .accounts({
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        admin: pg.wallet.publicKey,
+      })
+      .rpc();
+    console.log(`Scoring weights updated. Tx: ${txHash}`);
+
+    const configAccount = await pg.program.account.config.fetch(configPda);
+    assert.equal(configAccount.scoringWeights.spreadWeightBps, 30_000);
+    assert(configAccount.scoringWeights.executionCap.eq(new BN(1_000_000)));
+  });
+
+  it("rejects config updates from non-admins", async () => {
+    const intruderKp = new web3.Keypair();
+    try {
+      await pg.program.methods
+        .updateConfig({ ...configParams, rewardRate: new BN(1) })
+        .accounts({
+          config: configPda,
+          rewardPool: rewardPoolPda,
+          admin: intruderKp.publicKey,
+        })
+        .signers([intruderKp])
+        .rpc();
+      assert.fail("update_config should reject a non-admin signer");
+    } catch (err) {
+      assert(err.toString().includes("Unauthorized"));
+    }
+  });
+
+  it("registers a market maker", async () => {
+    const txHash = await pg.program.methods
+      .registerMarketMaker()
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Register market maker. Tx: ${txHash}`);
+
+    const marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.owner.equals(pg.wallet.publicKey));
+  });
+
+  it("registers a liquidity provider", async () => {
+    const txHash = await pg.program.methods
+      .registerLiquidityProvider()
+      .accounts({
+        liquidityProvider: liquidityProviderPda,
+        owner: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Register liquidity provider. Tx: ${txHash}`);
+
+    const providerAccount = await pg.program.account.liquidityProvider.fetch(
+      liquidityProviderPda
+    );
+    assert(providerAccount.owner.equals(pg.wallet.publicKey));
   });
 
   it("stakes tokens", async () => {
-    const marketMakerKp = new web3.Keypair();
     // Replace with valid public keys from your test environment.
     const stakerTokenAccount = new web3.PublicKey("StakerTokenAccountPublicKey");
-    const vaultTokenAccount = new web3.PublicKey("VaultTokenAccountPublicKey");
     const stakeAmount = new BN(500);
     const txHash = await pg.program.methods
       .stakeTokens(stakeAmount)
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
         stakerTokenAccount: stakerTokenAccount,
-        vault: vaultTokenAccount,
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        vault: vaultPda,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Stake tokens. Tx: ${txHash}`);
 
     const marketMakerAccount = await pg.program.account.marketMaker.fetch(
-      marketMakerKp.publicKey
+      marketMakerPda
     );
     assert(marketMakerAccount.stakedAmount.eq(stakeAmount));
   });
 
+  it("requests and completes an unstake", async () => {
+    const stakerTokenAccount = new web3.PublicKey("StakerTokenAccountPublicKey");
+    const unstakeAmount = new BN(100);
+    const before = await pg.program.account.marketMaker.fetch(marketMakerPda);
+
+    let txHash = await pg.program.methods
+      .requestUnstake(unstakeAmount)
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        rewardPool: rewardPoolPda,
+      })
+      .rpc();
+    console.log(`Request unstake. Tx: ${txHash}`);
+
+    // Pending stake stops counting immediately.
+    let marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.stakedAmount.eq(before.stakedAmount.sub(unstakeAmount)));
+    assert.equal(marketMakerAccount.unbonding.length, 1);
+
+    txHash = await pg.program.methods
+      .completeUnstake()
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        vault: vaultPda,
+        stakerTokenAccount: stakerTokenAccount,
+        vaultAuthority: vaultAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Complete unstake. Tx: ${txHash}`);
+
+    marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert.equal(marketMakerAccount.unbonding.length, 0);
+  });
+
+  it("sets priority tiers", async () => {
+    // Bronze, Silver and Gold.
+    const tiers = [
+      { stakeThreshold: new BN(300), rateLimitBudget: 50, rebateBoostBps: new BN(0), priorityWeight: 1 },
+      { stakeThreshold: new BN(1_000_000), rateLimitBudget: 200, rebateBoostBps: new BN(500), priorityWeight: 2 },
+      { stakeThreshold: new BN(5_000_000), rateLimitBudget: 1_000, rebateBoostBps: new BN(1_000), priorityWeight: 4 },
+    ];
+    const txHash = await pg.program.methods
+      .setPriorityTiers(tiers)
+      .accounts({ config: configPda, admin: pg.wallet.publicKey })
+      .rpc();
+    console.log(`Set priority tiers. Tx: ${txHash}`);
+
+    const configAccount = await pg.program.account.config.fetch(configPda);
+    assert.equal(configAccount.priorityTiers.length, 3);
+
+    // Thresholds must strictly increase.
+    try {
+      await pg.program.methods
+        .setPriorityTiers([tiers[1], tiers[0]])
+        .accounts({ config: configPda, admin: pg.wallet.publicKey })
+        .rpc();
+      assert.fail("set_priority_tiers should refuse unordered tiers");
+    } catch (err) {
+      assert(err.toString().includes("InvalidPriorityTiers"));
+    }
+
+    // Rebate boosts are capped at 100%.
+    try {
+      await pg.program.methods
+        .setPriorityTiers([{ ...tiers[0], rebateBoostBps: new BN(10_001) }])
+        .accounts({ config: configPda, admin: pg.wallet.publicKey })
+        .rpc();
+      assert.fail("set_priority_tiers should refuse a rebate boost above 100%");
+    } catch (err) {
+      assert(err.toString().includes("InvalidPriorityTiers"));
+    }
+  });
+
+  it("issues and revokes a priority ticket", async () => {
+    let txHash = await pg.program.methods
+      .requestPriorityExecution()
+      .accounts({ staker: marketMakerPda, owner: pg.wallet.publicKey, config: configPda })
+      .rpc();
+    console.log(`Request priority execution. Tx: ${txHash}`);
+
+    const slot = await pg.connection.getSlot();
+    let marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.priorityExpirySlot.gt(new BN(slot)));
+    const tier = await pg.program.methods
+      .getPriorityTier()
+      .accounts({ staker: marketMakerPda })
+      .view();
+    assert.equal(tier, marketMakerAccount.priorityTier);
+    assert(tier !== null);
+
+    // The CPI entry point for partner DEX programs reports the same tier.
+    const verifiedTier = await pg.program.methods
+      .verifyPriority()
+      .accounts({ staker: marketMakerPda, maker: pg.wallet.publicKey })
+      .view();
+    assert.equal(verifiedTier, tier);
+
+    // So does a DEX program calling it over CPI: the mock DEX returns the tier as a borsh
+    // `Option<u8>`.
+    let order = await simulateSubmitPriorityOrder();
+    assert.equal(order.err, null);
+    assert(order.returnData.programId === MOCK_DEX_PROGRAM_ID.toBase58());
+    const returned = Buffer.from(order.returnData.data[0], "base64");
+    assert.deepEqual([...returned], [1, tier]);
+
+    // Unstaking below the 300 threshold revokes the ticket.
+    const unstakeAmount = marketMakerAccount.stakedAmount.sub(new BN(250));
+    txHash = await pg.program.methods
+      .requestUnstake(unstakeAmount)
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        rewardPool: rewardPoolPda,
+      })
+      .rpc();
+    console.log(`Request unstake. Tx: ${txHash}`);
+
+    marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.priorityExpirySlot.isZero());
+    assert.equal(marketMakerAccount.priorityTier, null);
+    try {
+      await pg.program.methods
+        .verifyPriority()
+        .accounts({ staker: marketMakerPda, maker: pg.wallet.publicKey })
+        .view();
+      assert.fail("verify_priority should fail without a ticket");
+    } catch (err) {
+      assert(err.toString().includes("NoPriorityTicket"));
+    }
+    order = await simulateSubmitPriorityOrder();
+    assert.notEqual(order.err, null);
+    assert(order.logs.some((log) => log.includes("NoPriorityTicket")));
+
+    // ...and it cannot be renewed until stake is back above the threshold.
+    try {
+      await pg.program.methods
+        .requestPriorityExecution()
+        .accounts({ staker: marketMakerPda, owner: pg.wallet.publicKey, config: configPda })
+        .rpc();
+      assert.fail("request_priority_execution should require the stake threshold");
+    } catch (err) {
+      assert(err.toString().includes("NotEnoughStake"));
+    }
+
+    txHash = await pg.program.methods
+      .completeUnstake()
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        vault: vaultPda,
+        stakerTokenAccount: new web3.PublicKey("StakerTokenAccountPublicKey"),
+        vaultAuthority: vaultAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Complete unstake. Tx: ${txHash}`);
+  });
+
+  it("rejects market maker actions from a non-owner", async () => {
+    const intruderKp = new web3.Keypair();
+    try {
+      await pg.program.methods
+        .enableAutoCompound()
+        .accounts({
+          staker: marketMakerPda,
+          owner: intruderKp.publicKey,
+        })
+        .signers([intruderKp])
+        .rpc();
+      assert.fail("enable_auto_compound should reject a non-owner signer");
+    } catch (err) {
+      assert(err.toString().includes("Unauthorized"));
+    }
+  });
+
+  it("registers a performance reporter", async () => {
+    let txHash = await pg.program.methods
+      .initializeReporterRegistry()
+      .accounts({
+        config: configPda,
+        reporterRegistry: reporterRegistryPda,
+        admin: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Initialize reporter registry. Tx: ${txHash}`);
+
+    // The test wallet doubles as the reporter for the performance tests below.
+    txHash = await pg.program.methods
+      .addReporter(pg.wallet.publicKey)
+      .accounts({
+        config: configPda,
+        reporterRegistry: reporterRegistryPda,
+        admin: pg.wallet.publicKey,
+      })
+      .rpc();
+    console.log(`Add reporter. Tx: ${txHash}`);
+
+    const registry = await pg.program.account.reporterRegistry.fetch(reporterRegistryPda);
+    assert(registry.reporters[0].equals(pg.wallet.publicKey));
+  });
+
+  it("rejects performance updates from unregistered reporters", async () => {
+    const intruderKp = new web3.Keypair();
+    try {
+      await pg.program.methods
+        .updatePerformance(new BN(1_000_000), new BN(1_000_000), new BN(1_000_000), new BN(100))
+        .accounts({
+          staker: marketMakerPda,
+          reporter: intruderKp.publicKey,
+          config: configPda,
+          reporterRegistry: reporterRegistryPda,
+          epoch: epochPda,
+        })
+        .signers([intruderKp])
+        .rpc();
+      assert.fail("update_performance should reject an unregistered reporter");
+    } catch (err) {
+      assert(err.toString().includes("UnauthorizedReporter"));
+    }
+  });
+
   it("updates performance and distributes rewards", async () => {
-    const marketMakerKp = new web3.Keypair();
     const execVolume = new BN(200);
     const spreadEfficiency = new BN(100);
     const orderFlow = new BN(50);
 
     let txHash = await pg.program.methods
-      .updatePerformance(execVolume, spreadEfficiency, orderFlow)
+      .updatePerformance(execVolume, spreadEfficiency, orderFlow, new BN(1))
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        reporter: pg.wallet.publicKey,
+        config: configPda,
+        reporterRegistry: reporterRegistryPda,
+        epoch: epochPda,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Update performance. Tx: ${txHash}`);
 
+    // Replaying the same nonce must fail.
+    try {
+      await pg.program.methods
+        .updatePerformance(execVolume, spreadEfficiency, orderFlow, new BN(1))
+        .accounts({
+          staker: marketMakerPda,
+          reporter: pg.wallet.publicKey,
+          config: configPda,
+          reporterRegistry: reporterRegistryPda,
+          epoch: epochPda,
+        })
+        .rpc();
+      assert.fail("update_performance should reject a replayed nonce");
+    } catch (err) {
+      assert(err.toString().includes("StaleNonce"));
+    }
+
+    // A report signed off-chain is accepted through an ed25519 attestation.
+    const nonce = new BN(2);
+    const message = Buffer.concat([
+      marketMakerPda.toBuffer(),
+      execVolume.toArrayLike(Buffer, "le", 8),
+      spreadEfficiency.toArrayLike(Buffer, "le", 8),
+      orderFlow.toArrayLike(Buffer, "le", 8),
+      nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+    txHash = await pg.program.methods
+      .updatePerformanceWithAttestation(execVolume, spreadEfficiency, orderFlow, nonce)
+      .accounts({
+        staker: marketMakerPda,
+        config: configPda,
+        reporterRegistry: reporterRegistryPda,
+        epoch: epochPda,
+        instructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([
+        web3.Ed25519Program.createInstructionWithPrivateKey({
+          privateKey: pg.wallet.keypair.secretKey,
+          message,
+        }),
+      ])
+      .rpc();
+    console.log(`Update performance with attestation. Tx: ${txHash}`);
+
     txHash = await pg.program.methods
       .distributeRewards()
       .accounts({
-        staker: marketMakerKp.publicKey,
-        vault: new web3.PublicKey("VaultTokenAccountPublicKey"),
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        vault: vaultPda,
+        rewardVault: rewardVaultPda,
         stakerTokenAccount: new web3.PublicKey("StakerTokenAccountPublicKey"),
-        config: new web3.PublicKey("ConfigAccountPublicKey"),
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        epoch: epochPda,
+        vaultAuthority: vaultAuthority,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Distribute rewards. Tx: ${txHash}`);
+
+    // Settled rewards are cleared and the epoch snapshot is marked as paid, so a second
+    // distribution cannot pay the same performance again.
+    const marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.pendingRewards.isZero());
+    assert(marketMakerAccount.lastEpoch.rewarded);
+  });
+
+  it("deposits trading fees", async () => {
+    const fees = new BN(500);
+    const executionVolume = new BN(10_000);
+    const before = await pg.program.account.feeRebatePool.fetch(feeRebatePoolPda);
+    const txHash = await pg.program.methods
+      .depositFees(fees, executionVolume)
+      .accounts({
+        depositor: pg.wallet.publicKey,
+        depositorTokenAccount: new web3.PublicKey("DepositorTokenAccountPublicKey"),
+        config: configPda,
+        reporterRegistry: reporterRegistryPda,
+        feeRebatePool: feeRebatePoolPda,
+        feeVault: feeVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Deposit fees. Tx: ${txHash}`);
+
+    const feePool = await pg.program.account.feeRebatePool.fetch(feeRebatePoolPda);
+    assert(feePool.totalFees.eq(before.totalFees.add(fees)));
+    assert(feePool.totalExecutionVolume.eq(before.totalExecutionVolume.add(executionVolume)));
+  });
+
+  it("advances the epoch", async () => {
+    // Wait for the short test epoch to end.
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const before = await pg.program.account.epoch.fetch(epochPda);
+    const poolBefore = await pg.program.account.feeRebatePool.fetch(feeRebatePoolPda);
+    const txHash = await pg.program.methods
+      .advanceEpoch()
+      .accounts({
+        crank: crankAccounts,
+        epoch: epochPda,
+        feeRebatePool: feeRebatePoolPda,
+      })
+      .rpc();
+    console.log(`Advance epoch. Tx: ${txHash}`);
+
+    const epochAccount = await pg.program.account.epoch.fetch(epochPda);
+    assert(epochAccount.index.eq(before.index.addn(1)));
+    assert(epochAccount.totalVolume.isZero());
+
+    // The epoch's deposited fees become claimable against the larger of its summed maker
+    // volume and the depositors' reported volume, and the accumulators restart.
+    const feePool = await pg.program.account.feeRebatePool.fetch(feeRebatePoolPda);
+    assert(feePool.lastEpochIndex.eq(before.index));
+    assert(feePool.lastEpochVolume.eq(BN.max(before.totalVolume, poolBefore.totalExecutionVolume)));
+    assert(feePool.lastEpochReportedVolume.eq(poolBefore.totalExecutionVolume));
+    // Boosted shares are taken of the epoch's summed boosted volume.
+    assert(feePool.lastEpochBoostedVolume.eq(before.totalBoostedVolume));
+    assert(epochAccount.totalBoostedVolume.isZero());
+    assert(feePool.totalFees.isZero());
+    assert(feePool.totalExecutionVolume.isZero());
   });
 
   it("provides and withdraws liquidity", async () => {
-    const liquidityProviderKp = new web3.Keypair();
     const liquidityAmount = new BN(1000);
     const providerTokenAccount = new web3.PublicKey("ProviderTokenAccountPublicKey");
-    const poolTokenAccount = new web3.PublicKey("PoolTokenAccountPublicKey");
+    // Replace with the provider's token account for the LP share mint.
+    const providerLpTokenAccount = new web3.PublicKey("ProviderLpTokenAccountPublicKey");
 
     let txHash = await pg.program.methods
       .provideLiquidity(liquidityAmount)
       .accounts({
-        liquidityProvider: liquidityProviderKp.publicKey,
+        owner: pg.wallet.publicKey,
         providerTokenAccount: providerTokenAccount,
-        pool: poolTokenAccount,
+        config: configPda,
+        pool: poolPda,
+        lpMint: lpMintPda,
+        providerLpTokenAccount: providerLpTokenAccount,
+        vaultAuthority: vaultAuthority,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([liquidityProviderKp])
       .rpc();
     console.log(`Provide liquidity. Tx: ${txHash}`);
 
-    // Withdraw half of the liquidity.
+    // The first deposit into an empty pool mints shares 1:1.
+    const lpBalance = await pg.connection.getTokenAccountBalance(providerLpTokenAccount);
+    assert.equal(lpBalance.value.amount, liquidityAmount.toString());
+
+    // Stake half of the shares to earn liquidity rewards.
+    const stakedShares = liquidityAmount.div(new BN(2));
     txHash = await pg.program.methods
-      .withdrawLiquidity(liquidityAmount.div(new BN(2)))
+      .stakeLpShares(stakedShares)
       .accounts({
-        liquidityProvider: liquidityProviderKp.publicKey,
-        pool: poolTokenAccount,
+        liquidityProvider: liquidityProviderPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        lpRewardPool: lpRewardPoolPda,
+        providerLpTokenAccount: providerLpTokenAccount,
+        lpStakeVault: lpStakeVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Stake LP shares. Tx: ${txHash}`);
+
+    // Burn half of the unstaked shares straight from the wallet.
+    const withdrawnShares = stakedShares.div(new BN(2));
+    txHash = await pg.program.methods
+      .withdrawLiquidity(withdrawnShares)
+      .accounts({
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        pool: poolPda,
         userTokenAccount: new web3.PublicKey("UserTokenAccountPublicKey"),
+        lpMint: lpMintPda,
+        providerLpTokenAccount: providerLpTokenAccount,
+        vaultAuthority: vaultAuthority,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([liquidityProviderKp])
       .rpc();
     console.log(`Withdraw liquidity. Tx: ${txHash}`);
+
+    // Rewards accrue only on shares held in the stake vault.
+    const providerAccount = await pg.program.account.liquidityProvider.fetch(
+      liquidityProviderPda
+    );
+    assert(providerAccount.liquidity.eq(stakedShares));
+    assert(providerAccount.lastAccrualTime.gte(providerAccount.lastDepositTime));
+    const stakeVaultBalance = await pg.connection.getTokenAccountBalance(lpStakeVaultPda);
+    assert.equal(stakeVaultBalance.value.amount, stakedShares.toString());
+    const lpMintInfo = await pg.connection.getParsedAccountInfo(lpMintPda);
+    assert.equal(
+      (lpMintInfo.value.data as any).parsed.info.supply,
+      liquidityAmount.sub(withdrawnShares).toString()
+    );
   });
 
   it("claims fee rebate", async () => {
-    const marketMakerKp = new web3.Keypair();
-    const feeRebatePoolKp = new web3.Keypair();
     const stakerTokenAccount = new web3.PublicKey("StakerTokenAccountPublicKey");
-    const txHash = await pg.program.methods
-      .claimFeeRebate()
-      .accounts({
-        staker: marketMakerKp.publicKey,
-        feeRebatePool: feeRebatePoolKp.publicKey,
-        stakerTokenAccount: stakerTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([marketMakerKp])
-      .rpc();
+    const claimAccounts = {
+      staker: marketMakerPda,
+      owner: pg.wallet.publicKey,
+      config: configPda,
+      epoch: epochPda,
+      feeRebatePool: feeRebatePoolPda,
+      feeVault: feeVaultPda,
+      stakerTokenAccount: stakerTokenAccount,
+      vaultAuthority: vaultAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const txHash = await pg.program.methods.claimFeeRebate().accounts(claimAccounts).rpc();
     console.log(`Claim fee rebate. Tx: ${txHash}`);
+
+    // Each epoch's rebate is paid only once.
+    try {
+      await pg.program.methods.claimFeeRebate().accounts(claimAccounts).rpc();
+      assert.fail("claim_fee_rebate should refuse a second claim for the same epoch");
+    } catch (err) {
+      assert(err.toString().includes("NoRebateAvailable"));
+    }
   });
 
   it("locks liquidity", async () => {
-    const liquidityProviderKp = new web3.Keypair();
-    const lockDuration = new BN(3); // 3-month lock.
+    const lockDuration = new BN(90 * 86_400); // 3-month lock, in seconds.
     const txHash = await pg.program.methods
       .lockLiquidity(lockDuration)
       .accounts({
-        liquidityProvider: liquidityProviderKp.publicKey,
+        liquidityProvider: liquidityProviderPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        lpRewardPool: lpRewardPoolPda,
       })
-      .signers([liquidityProviderKp])
       .rpc();
     console.log(`Lock liquidity. Tx: ${txHash}`);
+
+    const providerAccount = await pg.program.account.liquidityProvider.fetch(
+      liquidityProviderPda
+    );
+    assert(providerAccount.rewardMultiplier.eq(new BN(15_000))); // 1.5x in basis points.
+    assert(providerAccount.unlockAt.sub(providerAccount.lockStart).eq(lockDuration));
+
+    // Shortening the lock is refused.
+    try {
+      await pg.program.methods
+        .lockLiquidity(new BN(30 * 86_400))
+        .accounts({
+          liquidityProvider: liquidityProviderPda,
+          owner: pg.wallet.publicKey,
+          config: configPda,
+          lpRewardPool: lpRewardPoolPda,
+        })
+        .rpc();
+      assert.fail("lock_liquidity should refuse to shorten a lock");
+    } catch (err) {
+      assert(err.toString().includes("LockCannotBeShortened"));
+    }
+
+    // With no early-exit penalty configured, locked shares cannot be unstaked.
+    try {
+      await pg.program.methods
+        .unstakeLpShares(new BN(1))
+        .accounts({
+          liquidityProvider: liquidityProviderPda,
+          owner: pg.wallet.publicKey,
+          config: configPda,
+          lpRewardPool: lpRewardPoolPda,
+          pool: poolPda,
+          lpMint: lpMintPda,
+          providerLpTokenAccount: new web3.PublicKey("ProviderLpTokenAccountPublicKey"),
+          lpStakeVault: lpStakeVaultPda,
+          treasury: treasuryPda,
+          vaultAuthority: vaultAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("unstake_lp_shares should refuse locked shares");
+    } catch (err) {
+      assert(err.toString().includes("LiquidityLocked"));
+    }
+  });
+
+  it("refuses to expire a live lock", async () => {
+    try {
+      await pg.program.methods
+        .expireLock()
+        .accounts({
+          config: configPda,
+          liquidityProvider: liquidityProviderPda,
+          lpRewardPool: lpRewardPoolPda,
+        })
+        .rpc();
+      assert.fail("expire_lock should refuse a lock that has not expired");
+    } catch (err) {
+      assert(err.toString().includes("LockNotExpired"));
+    }
   });
 
   it("enables auto compound", async () => {
-    const marketMakerKp = new web3.Keypair();
     const txHash = await pg.program.methods
       .enableAutoCompound()
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Enable auto compound. Tx: ${txHash}`);
   });
 
-  it("claims liquidity rewards", async () => {
-    const liquidityProviderKp = new web3.Keypair();
-    const poolTokenAccount = new web3.PublicKey("PoolTokenAccountPublicKey");
-    const providerTokenAccount = new web3.PublicKey("ProviderTokenAccountPublicKey");
-    const txHash = await pg.program.methods
-      .claimLiquidityRewards()
-      .accounts({
-        liquidityProvider: liquidityProviderKp.publicKey,
-        pool: poolTokenAccount,
-        providerTokenAccount: providerTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([liquidityProviderKp])
-      .rpc();
-    console.log(`Claim liquidity rewards. Tx: ${txHash}`);
+  it("refuses to claim liquidity rewards within 7 days of a deposit", async () => {
+    // The shares were staked moments ago, so the flash loan guard holds the rewards back.
+    try {
+      await pg.program.methods
+        .claimLiquidityRewards()
+        .accounts({
+          liquidityProvider: liquidityProviderPda,
+          owner: pg.wallet.publicKey,
+          config: configPda,
+          lpRewardPool: lpRewardPoolPda,
+          lpRewardVault: lpRewardVaultPda,
+          providerTokenAccount: new web3.PublicKey("ProviderTokenAccountPublicKey"),
+          vaultAuthority: vaultAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("claim_liquidity_rewards should refuse a claim within 7 days of a deposit");
+    } catch (err) {
+      assert(err.toString().includes("FlashLoanDetected"));
+    }
   });
 
   it("enforces activity slashing", async () => {
-    const marketMakerKp = new web3.Keypair();
-    const treasuryTokenAccount = new web3.PublicKey("TreasuryTokenAccountPublicKey");
-    const txHash = await pg.program.methods
-      .enforceActivitySlashing()
-      .accounts({
-        staker: marketMakerKp.publicKey,
-        treasury: treasuryTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([marketMakerKp])
-      .rpc();
-    console.log(`Enforce activity slashing. Tx: ${txHash}`);
+    // The maker traded recently, so it is not slashable and its stake stays in the vault.
+    try {
+      await pg.program.methods
+        .enforceActivitySlashing()
+        .accounts({
+          crank: crankAccounts,
+          staker: marketMakerPda,
+          rewardPool: rewardPoolPda,
+          vault: vaultPda,
+        })
+        .rpc();
+      assert.fail("enforce_activity_slashing should refuse an active maker");
+    } catch (err) {
+      assert(err.toString().includes("SlashingNotDue"));
+    }
   });
 
   it("enforces minimum trade volume", async () => {
-    const marketMakerKp = new web3.Keypair();
     const txHash = await pg.program.methods
       .enforceMinTradeVolume()
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Enforce min trade volume. Tx: ${txHash}`);
   });
 
   it("enforces stake cooldown", async () => {
-    const marketMakerKp = new web3.Keypair();
     const txHash = await pg.program.methods
       .enforceStakeCooldown()
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Enforce stake cooldown. Tx: ${txHash}`);
   });
 
+  it("tracks realized volatility from the oracle", async () => {
+    // The local oracle feed requires a program build with the `mock-oracle` feature.
+    let txHash = await pg.program.methods
+      .initializeOracleFeed()
+      .accounts({
+        oracleFeed: oracleFeedPda,
+        authority: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Initialize oracle feed. Tx: ${txHash}`);
+
+    // Sample two prices at least one sample interval apart: a 1% move over one interval is
+    // 100 bps of realized volatility, above the config's threshold of 50.
+    const publishTimes = [];
+    for (const price of [1_000_000, 1_010_000]) {
+      txHash = await pg.program.methods
+        .publishOracleFeed(new BN(price))
+        .accounts({ oracleFeed: oracleFeedPda, authority: pg.wallet.publicKey })
+        .rpc();
+      console.log(`Publish oracle feed. Tx: ${txHash}`);
+      publishTimes.push((await pg.program.account.oracleFeed.fetch(oracleFeedPda)).publishTime);
+      txHash = await pg.program.methods
+        .updateVolatility()
+        .accounts({
+          config: configPda,
+          volatilityOracle: oracleFeedPda,
+          volatilityState: volatilityStatePda,
+        })
+        .rpc();
+      console.log(`Update volatility. Tx: ${txHash}`);
+      await new Promise((resolve) => setTimeout(resolve, 1500));
+    }
+
+    const volatilityState = await pg.program.account.volatilityState.fetch(volatilityStatePda);
+    assert(volatilityState.sampleCount.eq(new BN(2)));
+    // Return of 10_000 ppm, squared and scaled to one sample interval.
+    const elapsed = publishTimes[1].sub(publishTimes[0]);
+    assert(
+      volatilityState.variance.eq(
+        new BN(100_000_000).mul(configParams.volatilitySampleInterval).div(elapsed)
+      )
+    );
+
+    // The same oracle publish cannot be sampled twice.
+    try {
+      await pg.program.methods
+        .updateVolatility()
+        .accounts({
+          config: configPda,
+          volatilityOracle: oracleFeedPda,
+          volatilityState: volatilityStatePda,
+        })
+        .rpc();
+      assert.fail("update_volatility should refuse an already-sampled price");
+    } catch (err) {
+      assert(err.toString().includes("StaleOracle"));
+    }
+  });
+
+  it("sets the volatility reward curve", async () => {
+    // 1x up to 50 bps of volatility, rising linearly to 3x at 150 bps.
+    const breakpoints = [
+      { volatilityBps: new BN(50), multiplierBps: new BN(10_000) },
+      { volatilityBps: new BN(150), multiplierBps: new BN(30_000) },
+    ];
+    const txHash = await pg.program.methods
+      .setRewardCurve(breakpoints)
+      .accounts({ config: configPda, admin: pg.wallet.publicKey })
+      .rpc();
+    console.log(`Set reward curve. Tx: ${txHash}`);
+
+    const configAccount = await pg.program.account.config.fetch(configPda);
+    assert.equal(configAccount.rewardCurve.length, 2);
+
+    // Multipliers may not fall as volatility rises.
+    try {
+      await pg.program.methods
+        .setRewardCurve([...breakpoints, { volatilityBps: new BN(200), multiplierBps: new BN(20_000) }])
+        .accounts({ config: configPda, admin: pg.wallet.publicKey })
+        .rpc();
+      assert.fail("set_reward_curve should refuse a non-monotonic curve");
+    } catch (err) {
+      assert(err.toString().includes("InvalidRewardCurve"));
+    }
+  });
+
   it("adjusts rewards by volatility", async () => {
-    const marketMakerKp = new web3.Keypair();
-    const volatilityIndex = new BN(60); // High volatility.
     const txHash = await pg.program.methods
-      .adjustRewardsByVolatility(volatilityIndex)
+      .adjustRewardsByVolatility()
       .accounts({
-        staker: marketMakerKp.publicKey,
+        staker: marketMakerPda,
+        config: configPda,
+        rewardPool: rewardPoolPda,
+        volatilityState: volatilityStatePda,
       })
-      .signers([marketMakerKp])
       .rpc();
     console.log(`Adjust rewards by volatility. Tx: ${txHash}`);
+
+    // The curve rises from 1x at 50 bps to 3x at 150 bps, so 100 bps of realized volatility
+    // (samples exactly one interval apart) is a 2x multiplier. The multiplier scales the
+    // maker's shares in the reward pool.
+    const volatilityState = await pg.program.account.volatilityState.fetch(volatilityStatePda);
+    const volatilityBps = Math.floor(Math.sqrt(volatilityState.variance.toNumber())) / 100;
+    const expectedMultiplier = Math.min(
+      30_000,
+      Math.max(10_000, 10_000 + Math.floor(((Math.floor(volatilityBps) - 50) * 20_000) / 100))
+    );
+    const marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.rewardMultiplier.eq(new BN(expectedMultiplier)));
+    assert(
+      marketMakerAccount.rewardShares.eq(
+        marketMakerAccount.stakedAmount.muln(expectedMultiplier).divn(10_000)
+      )
+    );
   });
 
   it("claims gas fee rebate", async () => {
-    const marketMakerKp = new web3.Keypair();
-    const gasFeePoolKp = new web3.Keypair();
     const stakerTokenAccount = new web3.PublicKey("StakerTokenAccountPublicKey");
-    const txHash = await pg.program.methods
-      .claimGasFeeRebate()
-      .accounts({
-        staker: marketMakerKp.publicKey,
-        stakerTokenAccount: stakerTokenAccount,
-        gasFeePool: gasFeePoolKp.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([marketMakerKp])
-      .rpc();
-    console.log(`Claim gas fee rebate. Tx: ${txHash}`);
+    // The maker's last finalized epoch traded 400, well below the 100_000 rebate threshold.
+    try {
+      await pg.program.methods
+        .claimGasFeeRebate()
+        .accounts({
+          staker: marketMakerPda,
+          owner: pg.wallet.publicKey,
+          stakerTokenAccount: stakerTokenAccount,
+          config: configPda,
+          epoch: epochPda,
+          gasFeePool: gasFeePoolPda,
+          gasVault: gasVaultPda,
+          vaultAuthority: vaultAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("claim_gas_fee_rebate should refuse low-volume makers");
+    } catch (err) {
+      assert(err.toString().includes("NotEligibleForGasRebate"));
+    }
+    const marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert.isFalse(marketMakerAccount.lastEpoch.gasRebateClaimed);
   });
 
   it("claims insurance payout", async () => {
-    const liquidityProviderKp = new web3.Keypair();
-    const insurancePoolKp = new web3.Keypair();
     const providerTokenAccount = new web3.PublicKey("ProviderTokenAccountPublicKey");
     const txHash = await pg.program.methods
       .claimInsurancePayout()
       .accounts({
-        liquidityProvider: liquidityProviderKp.publicKey,
-        insurancePool: insurancePoolKp.publicKey,
+        liquidityProvider: liquidityProviderPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        insurancePool: insurancePoolPda,
+        insuranceVault: insuranceVaultPda,
         providerTokenAccount: providerTokenAccount,
+        vaultAuthority: vaultAuthority,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
-      .signers([liquidityProviderKp])
       .rpc();
     console.log(`Claim insurance payout. Tx: ${txHash}`);
   });
+
+  it("refuses a voting period below the minimum", async () => {
+    const configAccount = await pg.program.account.config.fetch(configPda);
+    const action = { updateConfig: { params: configParams } };
+    try {
+      await pg.program.methods
+        .createProposal(Array(32).fill(1), configAccount.minVotingPeriod.subn(1), action)
+        .accounts({
+          config: configPda,
+          proposal: findProposalPda(configAccount.proposalCount),
+          proposer: pg.wallet.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .rpc();
+      assert.fail("create_proposal should refuse a voting period below min_voting_period");
+    } catch (err) {
+      assert(err.toString().includes("InvalidVotingPeriod"));
+    }
+  });
+
+  it("runs a governance proposal to completion", async () => {
+    const configBefore = await pg.program.account.config.fetch(configPda);
+    const proposalId = configBefore.proposalCount;
+    const proposalPda = findProposalPda(proposalId);
+    const action = {
+      updateConfig: { params: { ...configParams, unbondingPeriod: new BN(0), rewardRate: new BN(300) } },
+    };
+
+    let txHash = await pg.program.methods
+      .createProposal(Array(32).fill(1), new BN(1), action)
+      .accounts({
+        config: configPda,
+        proposal: proposalPda,
+        proposer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Create proposal. Tx: ${txHash}`);
+
+    let proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert(proposalAccount.proposalId.eq(proposalId));
+    assert.deepEqual(proposalAccount.status, { active: {} });
+
+    // Voting cannot be closed early.
+    try {
+      await pg.program.methods
+        .finalizeProposal()
+        .accounts({ config: configPda, proposal: proposalPda })
+        .rpc();
+      assert.fail("finalize_proposal should wait for the voting window to end");
+    } catch (err) {
+      assert(err.toString().includes("VotingNotEnded"));
+    }
+
+    // With no votes the proposal misses quorum and is defeated.
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    txHash = await pg.program.methods
+      .finalizeProposal()
+      .accounts({ config: configPda, proposal: proposalPda })
+      .rpc();
+    console.log(`Finalize proposal. Tx: ${txHash}`);
+
+    proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert.deepEqual(proposalAccount.status, { defeated: {} });
+
+    try {
+      await pg.program.methods
+        .executeProposal()
+        .accounts({ config: configPda, rewardPool: rewardPoolPda, proposal: proposalPda })
+        .rpc();
+      assert.fail("execute_proposal should refuse a defeated proposal");
+    } catch (err) {
+      assert(err.toString().includes("ProposalNotSucceeded"));
+    }
+  });
+
+  it("passes a stake-weighted proposal", async () => {
+    const configBefore = await pg.program.account.config.fetch(configPda);
+    const proposalId = configBefore.proposalCount;
+    const proposalPda = findProposalPda(proposalId);
+    const [voteRecordPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_record"), proposalPda.toBuffer(), marketMakerPda.toBuffer()],
+      pg.program.programId
+    );
+    const rewardRate = new BN(300);
+    const action = {
+      updateConfig: { params: { ...configParams, unbondingPeriod: new BN(0), rewardRate } },
+    };
+
+    let txHash = await pg.program.methods
+      .createProposal(Array(32).fill(2), new BN(3), action)
+      .accounts({
+        config: configPda,
+        proposal: proposalPda,
+        proposer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Create proposal. Tx: ${txHash}`);
+
+    const voteAccounts = {
+      staker: marketMakerPda,
+      voter: pg.wallet.publicKey,
+      config: configPda,
+      proposal: proposalPda,
+      voteRecord: voteRecordPda,
+      systemProgram: web3.SystemProgram.programId,
+    };
+    txHash = await pg.program.methods.castVote({ against: {} }).accounts(voteAccounts).rpc();
+    console.log(`Cast vote. Tx: ${txHash}`);
+
+    // The vote weight is the maker's stake before the proposal was created.
+    const stakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    let proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert(proposalAccount.votesAgainst.eq(stakerAccount.stakedAmount));
+
+    // A second vote from the same maker is refused by the vote record...
+    try {
+      await pg.program.methods.castVote({ for: {} }).accounts(voteAccounts).rpc();
+      assert.fail("cast_vote should refuse a second vote");
+    } catch (err) {
+      assert(err.toString().includes("already in use"));
+    }
+
+    // ...but the existing vote can be changed while voting is open.
+    txHash = await pg.program.methods
+      .changeVote({ for: {} })
+      .accounts({
+        staker: marketMakerPda,
+        voter: pg.wallet.publicKey,
+        config: configPda,
+        proposal: proposalPda,
+        voteRecord: voteRecordPda,
+      })
+      .rpc();
+    console.log(`Change vote. Tx: ${txHash}`);
+
+    proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert(proposalAccount.votesAgainst.isZero());
+    assert(proposalAccount.votesFor.eq(stakerAccount.stakedAmount));
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    txHash = await pg.program.methods
+      .finalizeProposal()
+      .accounts({ config: configPda, proposal: proposalPda })
+      .rpc();
+    console.log(`Finalize proposal. Tx: ${txHash}`);
+    txHash = await pg.program.methods
+      .executeProposal()
+      .accounts({ config: configPda, rewardPool: rewardPoolPda, proposal: proposalPda })
+      .rpc();
+    console.log(`Execute proposal. Tx: ${txHash}`);
+
+    proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert.deepEqual(proposalAccount.status, { executed: {} });
+    const configAccount = await pg.program.account.config.fetch(configPda);
+    assert(configAccount.rewardRate.eq(rewardRate));
+    // Executing bumps the config version, so older `UpdateConfig` proposals become stale.
+    assert(configAccount.configVersion.eq(proposalAccount.configVersion.addn(1)));
+  });
+
+  it("votes through a delegate", async () => {
+    const delegateKp = new web3.Keypair();
+    // The delegate pays for its vote record.
+    await web3.sendAndConfirmTransaction(
+      pg.connection,
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: pg.wallet.publicKey,
+          toPubkey: delegateKp.publicKey,
+          lamports: web3.LAMPORTS_PER_SOL / 100,
+        })
+      ),
+      [pg.wallet.keypair]
+    );
+
+    let txHash = await pg.program.methods
+      .delegateVotes(delegateKp.publicKey)
+      .accounts({ staker: marketMakerPda, owner: pg.wallet.publicKey })
+      .rpc();
+    console.log(`Delegate votes. Tx: ${txHash}`);
+
+    const configBefore = await pg.program.account.config.fetch(configPda);
+    const proposalPda = findProposalPda(configBefore.proposalCount);
+    const [voteRecordPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vote_record"), proposalPda.toBuffer(), marketMakerPda.toBuffer()],
+      pg.program.programId
+    );
+    const action = { updateConfig: { params: { ...configParams, unbondingPeriod: new BN(0) } } };
+    await pg.program.methods
+      .createProposal(Array(32).fill(3), new BN(60), action)
+      .accounts({
+        config: configPda,
+        proposal: proposalPda,
+        proposer: pg.wallet.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    const voteAccounts = (voter: web3.PublicKey) => ({
+      staker: marketMakerPda,
+      voter,
+      config: configPda,
+      proposal: proposalPda,
+      voteRecord: voteRecordPda,
+      systemProgram: web3.SystemProgram.programId,
+    });
+
+    // While delegated, the owner's hot key cannot vote.
+    try {
+      await pg.program.methods.castVote({ for: {} }).accounts(voteAccounts(pg.wallet.publicKey)).rpc();
+      assert.fail("cast_vote should refuse the owner while votes are delegated");
+    } catch (err) {
+      assert(err.toString().includes("Unauthorized"));
+    }
+
+    txHash = await pg.program.methods
+      .castVote({ for: {} })
+      .accounts(voteAccounts(delegateKp.publicKey))
+      .signers([delegateKp])
+      .rpc();
+    console.log(`Delegate casts vote. Tx: ${txHash}`);
+
+    const stakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    const proposalAccount = await pg.program.account.proposal.fetch(proposalPda);
+    assert(proposalAccount.votesFor.eq(stakerAccount.stakedAmount));
+    const voteRecord = await pg.program.account.voteRecord.fetch(voteRecordPda);
+    assert(voteRecord.voter.equals(delegateKp.publicKey));
+
+    txHash = await pg.program.methods
+      .undelegateVotes()
+      .accounts({ staker: marketMakerPda, owner: pg.wallet.publicKey })
+      .rpc();
+    console.log(`Undelegate votes. Tx: ${txHash}`);
+    assert.isNull((await pg.program.account.marketMaker.fetch(marketMakerPda)).delegate);
+  });
+
+  it("auctions priority slots", async () => {
+    const stakerTokenAccount = new web3.PublicKey("StakerTokenAccountPublicKey");
+    // Auction a single slot per epoch; any staker may bid. Bidding stays open long enough for
+    // both bidders' transactions.
+    let txHash = await pg.program.methods
+      .updateConfig({
+        ...configParams,
+        unbondingPeriod: new BN(0),
+        priorityStakeThreshold: new BN(0),
+        auctionSlots: 1,
+        auctionDuration: new BN(15),
+      })
+      .accounts({ config: configPda, rewardPool: rewardPoolPda, admin: pg.wallet.publicKey })
+      .rpc();
+    console.log(`Enable priority auction. Tx: ${txHash}`);
+
+    // Tickets can no longer be requested directly.
+    try {
+      await pg.program.methods
+        .requestPriorityExecution()
+        .accounts({ staker: marketMakerPda, owner: pg.wallet.publicKey, config: configPda })
+        .rpc();
+      assert.fail("request_priority_execution should be refused while slots are auctioned");
+    } catch (err) {
+      assert(err.toString().includes("PriorityAuctioned"));
+    }
+
+    const epochAccount = await pg.program.account.epoch.fetch(epochPda);
+    const [auctionPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("priority_auction"), configPda.toBuffer(), epochAccount.index.toArrayLike(Buffer, "le", 8)],
+      pg.program.programId
+    );
+    const [escrowPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("auction_escrow"), auctionPda.toBuffer()],
+      pg.program.programId
+    );
+    const [bidPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("priority_bid"), auctionPda.toBuffer(), marketMakerPda.toBuffer()],
+      pg.program.programId
+    );
+
+    // A rival maker bids against the wallet for the single slot.
+    const rival = web3.Keypair.generate();
+    // Replace with the rival's HFMMT token account.
+    const rivalTokenAccount = new web3.PublicKey("RivalTokenAccountPublicKey");
+    const [rivalMakerPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("market_maker"), rival.publicKey.toBuffer()],
+      pg.program.programId
+    );
+    const [rivalBidPda] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("priority_bid"), auctionPda.toBuffer(), rivalMakerPda.toBuffer()],
+      pg.program.programId
+    );
+    await web3.sendAndConfirmTransaction(
+      pg.connection,
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: pg.wallet.publicKey,
+          toPubkey: rival.publicKey,
+          lamports: web3.LAMPORTS_PER_SOL / 10,
+        })
+      ),
+      [pg.wallet.keypair]
+    );
+    txHash = await pg.program.methods
+      .registerMarketMaker()
+      .accounts({
+        staker: rivalMakerPda,
+        owner: rival.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([rival])
+      .rpc();
+    console.log(`Register rival market maker. Tx: ${txHash}`);
+
+    txHash = await pg.program.methods
+      .openPriorityAuction()
+      .accounts({
+        config: configPda,
+        epoch: epochPda,
+        auction: auctionPda,
+        mint: hfmmtMint,
+        escrow: escrowPda,
+        vaultAuthority: vaultAuthority,
+        payer: pg.wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .rpc();
+    console.log(`Open priority auction. Tx: ${txHash}`);
+
+    // The rival fills the open slot first.
+    txHash = await pg.program.methods
+      .placePriorityBid(new BN(100))
+      .accounts({
+        staker: rivalMakerPda,
+        owner: rival.publicKey,
+        config: configPda,
+        auction: auctionPda,
+        bid: rivalBidPda,
+        escrow: escrowPda,
+        stakerTokenAccount: rivalTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([rival])
+      .rpc();
+    console.log(`Place rival priority bid. Tx: ${txHash}`);
+
+    const placeBidAccounts = {
+      staker: marketMakerPda,
+      owner: pg.wallet.publicKey,
+      config: configPda,
+      auction: auctionPda,
+      bid: bidPda,
+      escrow: escrowPda,
+      stakerTokenAccount: stakerTokenAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: web3.SystemProgram.programId,
+    };
+    // Matching the lowest winning bid is not enough: ties keep the earlier bid.
+    try {
+      await pg.program.methods.placePriorityBid(new BN(100)).accounts(placeBidAccounts).rpc();
+      assert.fail("place_priority_bid should refuse a bid that does not beat the lowest winner");
+    } catch (err) {
+      assert(err.toString().includes("BidTooLow"));
+    }
+
+    // Outbidding displaces the rival.
+    txHash = await pg.program.methods.placePriorityBid(new BN(120)).accounts(placeBidAccounts).rpc();
+    console.log(`Place priority bid. Tx: ${txHash}`);
+
+    let auctionAccount = await pg.program.account.priorityAuction.fetch(auctionPda);
+    assert.equal(auctionAccount.leaders.length, 1);
+    assert(auctionAccount.leaders[0].bidder.equals(marketMakerPda));
+
+    txHash = await pg.program.methods
+      .raisePriorityBid(new BN(30))
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        auction: auctionPda,
+        bid: bidPda,
+        escrow: escrowPda,
+        stakerTokenAccount: stakerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Raise priority bid. Tx: ${txHash}`);
+
+    // The displaced rival's raise to 110 still falls short of the winning 150.
+    try {
+      await pg.program.methods
+        .raisePriorityBid(new BN(10))
+        .accounts({
+          staker: rivalMakerPda,
+          owner: rival.publicKey,
+          config: configPda,
+          auction: auctionPda,
+          bid: rivalBidPda,
+          escrow: escrowPda,
+          stakerTokenAccount: rivalTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([rival])
+        .rpc();
+      assert.fail("raise_priority_bid should refuse a total that does not beat the lowest winner");
+    } catch (err) {
+      assert(err.toString().includes("BidTooLow"));
+    }
+
+    auctionAccount = await pg.program.account.priorityAuction.fetch(auctionPda);
+    assert.equal(auctionAccount.leaders.length, 1);
+    assert(auctionAccount.leaders[0].bidder.equals(marketMakerPda));
+    assert(auctionAccount.leaders[0].amount.eq(new BN(150)));
+
+    // Wait out the bidding window, then settle into the treasury.
+    const biddingLeftMs = (auctionAccount.endTime.toNumber() + 2) * 1000 - Date.now();
+    await new Promise((resolve) => setTimeout(resolve, Math.max(biddingLeftMs, 0)));
+    const treasuryBefore = await pg.connection.getTokenAccountBalance(treasuryPda);
+    // Settlement rechecks each leader's stake, so the leaders' market makers are passed in order.
+    txHash = await pg.program.methods
+      .settlePriorityAuction()
+      .accounts({ crank: crankAccounts, auction: auctionPda, escrow: escrowPda })
+      .remainingAccounts([{ pubkey: marketMakerPda, isSigner: false, isWritable: false }])
+      .rpc();
+    console.log(`Settle priority auction. Tx: ${txHash}`);
+
+    auctionAccount = await pg.program.account.priorityAuction.fetch(auctionPda);
+    assert(auctionAccount.settled);
+    assert.isFalse(auctionAccount.leaders[0].forfeited);
+    assert(auctionAccount.ticketExpirySlot.gt(new BN(0)));
+    const treasuryAfter = await pg.connection.getTokenAccountBalance(treasuryPda);
+    // Proceeds arrive before the keeper's flat fee is paid out.
+    assert(new BN(treasuryAfter.value.amount).gte(new BN(treasuryBefore.value.amount).add(new BN(140))));
+
+    txHash = await pg.program.methods
+      .claimPriorityBid()
+      .accounts({
+        staker: marketMakerPda,
+        owner: pg.wallet.publicKey,
+        config: configPda,
+        auction: auctionPda,
+        bid: bidPda,
+        escrow: escrowPda,
+        stakerTokenAccount: stakerTokenAccount,
+        vaultAuthority: vaultAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(`Claim priority bid. Tx: ${txHash}`);
+
+    // The winner holds a ticket for the auction's term and its bid account is closed.
+    const slot = await pg.connection.getSlot();
+    const marketMakerAccount = await pg.program.account.marketMaker.fetch(marketMakerPda);
+    assert(marketMakerAccount.priorityExpirySlot.gt(new BN(slot)));
+    assert(marketMakerAccount.priorityExpirySlot.eq(auctionAccount.ticketExpirySlot));
+    assert.isNull(await pg.connection.getAccountInfo(bidPda));
+
+    // The displaced rival gets no ticket and its escrowed 100 back.
+    txHash = await pg.program.methods
+      .claimPriorityBid()
+      .accounts({
+        staker: rivalMakerPda,
+        owner: rival.publicKey,
+        config: configPda,
+        auction: auctionPda,
+        bid: rivalBidPda,
+        escrow: escrowPda,
+        stakerTokenAccount: rivalTokenAccount,
+        vaultAuthority: vaultAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([rival])
+      .rpc();
+    console.log(`Claim rival priority bid. Tx: ${txHash}`);
+
+    const rivalMakerAccount = await pg.program.account.marketMaker.fetch(rivalMakerPda);
+    assert(rivalMakerAccount.priorityExpirySlot.isZero());
+    assert.isNull(await pg.connection.getAccountInfo(rivalBidPda));
+    const escrowBalance = await pg.connection.getTokenAccountBalance(escrowPda);
+    assert.equal(escrowBalance.value.amount, "0");
+  });
 });
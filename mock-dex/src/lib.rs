@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use hfmmt::cpi::accounts::VerifyPriority;
+use hfmmt::program::HfmmToken;
+
+declare_id!("DSY9vNdarQiTmuJpd7ob9FnNSFBAozoitCtVmn3dmbPJ");
+
+/// Minimal matching engine used to exercise HFMMT's `verify_priority` CPI. It only accepts
+/// priority orders from makers holding a valid HFMMT priority ticket.
+#[program]
+pub mod mock_dex {
+    use super::*;
+
+    /// Accept a priority order from `maker`, returning the maker's HFMMT priority tier as
+    /// return data. Fails with HFMMT's `NoPriorityTicket` if the maker holds no valid ticket.
+    pub fn submit_priority_order(ctx: Context<SubmitPriorityOrder>) -> Result<Option<u8>> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.hfmmt_program.to_account_info(),
+            VerifyPriority {
+                staker: ctx.accounts.staker.to_account_info(),
+                maker: ctx.accounts.maker.to_account_info(),
+            },
+        );
+        let tier = hfmmt::cpi::verify_priority(cpi_ctx)?.get();
+        emit!(PriorityOrderAccepted {
+            maker: ctx.accounts.maker.key(),
+            tier,
+        });
+        Ok(tier)
+    }
+}
+
+#[derive(Accounts)]
+pub struct SubmitPriorityOrder<'info> {
+    /// CHECK: HFMMT `MarketMaker` account of `maker`; HFMMT checks it during the CPI.
+    pub staker: UncheckedAccount<'info>,
+    pub maker: Signer<'info>,
+    pub hfmmt_program: Program<'info, HfmmToken>,
+}
+
+#[event]
+pub struct PriorityOrderAccepted {
+    pub maker: Pubkey,
+    pub tier: Option<u8>,
+}